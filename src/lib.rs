@@ -10,7 +10,62 @@ use std::{error::Error, fmt, hash::Hash};
 
 pub use {
     error::{EvictError, EvictResult},
-    replacer::{LruKConfig, LruKReplacer, LruReplacer},
+    replacer::{
+        AgingConfig,
+        AgingReplacer,
+        ArcReplacer,
+        BeladyReplacer,
+        CarConfig,
+        CarReplacer,
+        ClockProReplacer,
+        ClockReplacer,
+        DenseClockReplacer,
+        DenseRripReplacer,
+        EelruConfig,
+        EelruReplacer,
+        FbrConfig,
+        FbrReplacer,
+        FifoReplacer,
+        GclockConfig,
+        GclockReplacer,
+        GdsfReplacer,
+        HyperbolicConfig,
+        HyperbolicReplacer,
+        LecarConfig,
+        LecarReplacer,
+        LfuConfig,
+        LfuReplacer,
+        LhdConfig,
+        LhdReplacer,
+        LirsConfig,
+        LirsReplacer,
+        LrfuConfig,
+        LrfuReplacer,
+        LruKConfig,
+        LruKReplacer,
+        LruReplacer,
+        MqConfig,
+        MqReplacer,
+        MruReplacer,
+        RandomReplacer,
+        RripConfig,
+        RripInsertion,
+        RripReplacer,
+        S3FifoConfig,
+        S3FifoReplacer,
+        SampledConfig,
+        SampledPolicy,
+        SampledReplacer,
+        SieveReplacer,
+        SlruConfig,
+        SlruReplacer,
+        TwoQConfig,
+        TwoQReplacer,
+        WTinyLfuConfig,
+        WTinyLfuReplacer,
+        WsClockConfig,
+        WsClockReplacer,
+    },
 };
 
 /// Frame identifier type.
@@ -28,7 +83,15 @@ impl<T> FrameId for T where T: Copy + Hash + Eq + fmt::Display + fmt::Debug {}
 /// nature of the access. For example, a page might be accessed for reading a
 /// single data point in it or for scanning of the whole page -- policies might
 /// want to distinguish between these access patterns.
-pub trait AccessType {}
+pub trait AccessType {
+    /// Whether the page is accessed as a part of a sequential scan.
+    ///
+    /// Scan-resistant policies might use this hint to prevent one-off scan
+    /// accesses from displacing frequently accessed pages.
+    fn is_scan(&self) -> bool {
+        false
+    }
+}
 
 /// Page eviction policy.
 ///
@@ -0,0 +1,315 @@
+//! Aging (NRU with reference history) page replacement algorithm.
+//!
+//! The algorithm implemented here is the software approximation of LRU, as
+//! described in "Modern Operating Systems" by Tanenbaum.
+
+use {
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{
+        collections::HashMap,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+    },
+};
+
+/// Configuration of the Aging replacer.
+#[derive(Debug)]
+pub struct AgingConfig {
+    /// Maximum number of frames that can be stored in the replacer.
+    pub capacity: usize,
+
+    /// Number of bits of the age register, clamped to `1..=64`.
+    ///
+    /// The register remembers whether the frame has been referenced during
+    /// each of the last `bits` ticks. With a single bit, the replacer works
+    /// as NRU (Not Recently Used).
+    pub bits: u8,
+}
+
+impl Default for AgingConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            bits: 8,
+        }
+    }
+}
+
+/// State of a slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// No frame is stored in the slot.
+    Empty,
+
+    /// Frame is stored, but is not a candidate for eviction.
+    Pinned,
+
+    /// Frame is stored, and can be evicted.
+    Evictable,
+}
+
+/// Slot of the frame table.
+#[derive(Debug)]
+struct Slot {
+    /// Reference bit, set on every access, and shifted into the age register
+    /// on every tick.
+    ///
+    /// The bit is atomic, so that it can be set while holding a read lock.
+    referenced: AtomicBool,
+
+    /// Age register, the most significant bit corresponds to the last tick.
+    age: u64,
+
+    state: State,
+}
+
+impl Slot {
+    /// Eviction key of the frame, the frame with the smallest key is evicted
+    /// first.
+    ///
+    /// Among the frames with equal registers, the ones not referenced since
+    /// the last tick go first.
+    fn key(&self) -> (u64, bool) {
+        (self.age, self.referenced.load(Ordering::Relaxed))
+    }
+}
+
+/// Aging frame replacer.
+///
+/// Every frame has a reference bit, which is set on every access, and an age
+/// register (see [`AgingConfig::bits`]). On every [`tick`](Self::tick), the
+/// registers of all the frames are shifted right, with the reference bits
+/// shifted into their most significant bits, and the reference bits are
+/// cleared. The register is thus the history of references to the frame over
+/// the last few ticks, with the recent ones weighing more, and the frame with
+/// the smallest register is evicted first. New frames are treated as if they
+/// were referenced just before the last tick.
+///
+/// The replacer does not keep time on its own: ticks are expected to be
+/// driven externally (e.g. by a background timer). Accessing a frame which is
+/// already stored only sets its reference bit, so it requires a read lock
+/// only. Eviction scans the frames starting from the position past the last
+/// victim, so that the frames with equal registers are evicted in a round
+/// robin fashion, and stops early at the frame which has not been referenced
+/// at all during the last `bits` ticks.
+///
+/// Pinned frames keep aging, but are never picked as victims.
+pub struct AgingReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: AgingConfig,
+
+    slots: Vec<Slot>,
+
+    /// Position the search for a victim starts from.
+    hand: usize,
+
+    /// Number of evictable frames.
+    size: usize,
+
+    /// Mapping of frame IDs to their slots.
+    ids: HashMap<F, usize>,
+
+    /// Frame IDs stored in slots.
+    frames: Vec<Option<F>>,
+
+    /// Empty slots.
+    free: Vec<usize>,
+}
+
+impl<F: FrameId> Default for AgingReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(AgingConfig::default())
+    }
+}
+
+impl<F: FrameId> AgingReplacer<F> {
+    /// Creates a new Aging replacer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(AgingConfig {
+            capacity,
+            ..AgingConfig::default()
+        })
+    }
+
+    /// Creates a new Aging replacer with the given configuration.
+    pub fn with_config(config: AgingConfig) -> Self {
+        let capacity = config.capacity;
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                config,
+                slots: (0..capacity)
+                    .map(|_| Slot {
+                        referenced: AtomicBool::new(false),
+                        age: 0,
+                        state: State::Empty,
+                    })
+                    .collect(),
+                hand: 0,
+                size: 0,
+                ids: HashMap::with_capacity(capacity),
+                frames: vec![None; capacity],
+                // Slots are taken from the back, start with the lowest one.
+                free: (0..capacity).rev().collect(),
+            })),
+        }
+    }
+
+    /// Shifts the reference bits of all the frames into their age registers,
+    /// and clears the reference bits.
+    pub fn tick(&self) {
+        let mut inner = self.inner.write();
+        let top = inner.top_bit();
+        for s in inner.slots.iter_mut().filter(|s| s.state != State::Empty) {
+            let referenced = std::mem::take(s.referenced.get_mut());
+            s.age = (s.age >> 1) | if referenced { top } else { 0 };
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    /// Most significant bit of the age register.
+    fn top_bit(&self) -> u64 {
+        1 << (self.config.bits.clamp(1, 64) - 1)
+    }
+
+    fn set_state(&mut self, slot: usize, state: State) {
+        let s = &mut self.slots[slot];
+        match (s.state, state) {
+            (State::Evictable, State::Pinned | State::Empty) => self.size -= 1,
+            (State::Pinned | State::Empty, State::Evictable) => self.size += 1,
+            _ => {}
+        }
+        s.state = state;
+    }
+
+    /// Finds the evictable frame with the smallest eviction key.
+    fn victim(&self) -> Option<usize> {
+        if self.size == 0 {
+            return None;
+        }
+        let n = self.slots.len();
+        let evictable = (0..n)
+            .map(|i| (self.hand + i) % n)
+            .filter(|&slot| self.slots[slot].state == State::Evictable);
+
+        let mut victim: Option<(usize, (u64, bool))> = None;
+        for slot in evictable {
+            let key = self.slots[slot].key();
+            // Cannot do any better than a frame which has not been referenced at all.
+            if key == (0, false) {
+                return Some(slot);
+            }
+            if victim.is_none_or(|(_, lowest)| key < lowest) {
+                victim = Some((slot, key));
+            }
+        }
+        victim.map(|(slot, _)| slot)
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for AgingReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let slot = inner.victim()?;
+        inner.hand = (slot + 1) % inner.slots.len();
+        inner.set_state(slot, State::Empty);
+        let id = inner.frames[slot].take()?;
+        inner.ids.remove(&id);
+        inner.free.push(slot);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        let inner = self.inner.read();
+        inner.victim().and_then(|slot| inner.frames[slot].clone())
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        // Fast path: the frame is already stored, just set its reference bit.
+        {
+            let inner = self.inner.read();
+            if let Some(&slot) = inner.ids.get(&id) {
+                inner.slots[slot].referenced.store(true, Ordering::Relaxed);
+                return Ok(());
+            }
+        }
+
+        let mut inner = self.inner.write();
+        if let Some(&slot) = inner.ids.get(&id) {
+            inner.slots[slot].referenced.store(true, Ordering::Relaxed);
+            return Ok(());
+        }
+
+        let slot = inner.free.pop().ok_or(EvictError::FrameReplacerFull)?;
+        let top = inner.top_bit();
+        let s = &mut inner.slots[slot];
+        *s.referenced.get_mut() = false;
+        s.age = top;
+        inner.set_state(slot, State::Evictable);
+        inner.frames[slot] = Some(id.clone());
+        inner.ids.insert(id, slot);
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // Aging does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let slot = *inner.ids.get(&id).ok_or(EvictError::InvalidFrameId(id))?;
+        inner.set_state(slot, State::Pinned);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(&slot) = inner.ids.get(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+        inner.set_state(slot, State::Evictable);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let Some(&slot) = inner.ids.get(&id) else {
+            return Ok(());
+        };
+        if inner.slots[slot].state == State::Pinned {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+        inner.set_state(slot, State::Empty);
+        inner.frames[slot] = None;
+        inner.ids.remove(&id);
+        inner.free.push(slot);
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().size
+    }
+}
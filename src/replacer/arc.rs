@@ -0,0 +1,227 @@
+//! ARC page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the [ARC paper](https://www.usenix.org/legacy/events/fast03/tech/full_papers/megiddo/megiddo.pdf).
+
+use {
+    super::list::FrameList,
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{collections::HashSet, sync::Arc},
+};
+
+/// Adaptive Replacement Cache (ARC) frame replacer.
+///
+/// Resident frames are split between two LRU lists: `T1` holds frames that
+/// have been referenced only once recently, while `T2` holds frames that have
+/// been referenced at least twice. Identifiers of frames evicted from `T1` and
+/// `T2` are remembered in ghost lists `B1` and `B2` respectively.
+///
+/// A reference to a ghost frame signals that the corresponding list was too
+/// small, so the target size `p` of `T1` is adapted: hits in `B1` grow it
+/// (favouring recency), hits in `B2` shrink it (favouring frequency). Victims
+/// are taken from `T1` while it is larger than `p`, and from `T2` otherwise.
+///
+/// Pinned frames stay in their lists (so they keep their history), but are
+/// never picked as victims.
+pub struct ArcReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Maximum number of resident frames (`c` in the paper).
+    capacity: usize,
+
+    /// Target size of `T1`.
+    p: usize,
+
+    /// Resident frames referenced once recently.
+    t1: FrameList<F>,
+
+    /// Resident frames referenced at least twice recently.
+    t2: FrameList<F>,
+
+    /// Ghost frames recently evicted from `T1`.
+    b1: FrameList<F>,
+
+    /// Ghost frames recently evicted from `T2`.
+    b2: FrameList<F>,
+
+    /// Resident frames which are not candidates for eviction.
+    pinned: HashSet<F>,
+}
+
+/// Resident list the victim is taken from.
+#[derive(Clone, Copy)]
+enum Segment {
+    T1,
+    T2,
+}
+
+impl<F: FrameId> ArcReplacer<F> {
+    /// Creates a new ARC replacer.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                capacity,
+                p: 0,
+                t1: FrameList::with_capacity(capacity),
+                t2: FrameList::with_capacity(capacity),
+                b1: FrameList::with_capacity(capacity),
+                b2: FrameList::with_capacity(capacity),
+                pinned: HashSet::new(),
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    fn is_resident(&self, id: &F) -> bool {
+        self.t1.contains(id) || self.t2.contains(id)
+    }
+
+    fn resident(&self) -> usize {
+        self.t1.len() + self.t2.len()
+    }
+
+    /// Finds the next victim along with the list it resides in.
+    fn victim(&self) -> Option<(F, Segment)> {
+        let first_evictable =
+            |list: &FrameList<F>| list.iter().find(|id| !self.pinned.contains(*id)).cloned();
+        let t1 = first_evictable(&self.t1);
+        let t2 = first_evictable(&self.t2);
+
+        // Evict from `T1` if it exceeds its target size, or if there is nothing
+        // else to evict.
+        match (t1, t2) {
+            (Some(id), None) => Some((id, Segment::T1)),
+            (Some(id), Some(_)) if self.t1.len() > self.p => Some((id, Segment::T1)),
+            (_, Some(id)) => Some((id, Segment::T2)),
+            (None, None) => None,
+        }
+    }
+
+    /// Inserts a frame which is neither resident nor remembered in ghost lists.
+    fn insert_new(&mut self, id: F) {
+        // Keep directory within bounds: `|T1| + |B1| <= c` and the total size of
+        // all the lists is `<= 2c`.
+        if self.t1.len() + self.b1.len() >= self.capacity {
+            self.b1.pop_front();
+        } else if self.resident() + self.b1.len() + self.b2.len() >= 2 * self.capacity {
+            self.b2.pop_front();
+        }
+        self.t1.push_back(id);
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for ArcReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let (id, segment) = inner.victim()?;
+
+        // Remember the identifier of the victim in the corresponding ghost list.
+        match segment {
+            Segment::T1 => {
+                inner.t1.remove(&id);
+                inner.b1.push_back(id.clone());
+            }
+            Segment::T2 => {
+                inner.t2.remove(&id);
+                inner.b2.push_back(id.clone());
+            }
+        }
+
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        self.inner.read().victim().map(|(id, _)| id)
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Cache hit: the frame has been referenced at least twice, move it to the
+        // MRU position of `T2`.
+        if inner.t1.remove(&id) || inner.t2.contains(&id) {
+            inner.t2.push_back(id);
+            return Ok(());
+        }
+
+        // Cache miss: make sure that there is room for a new resident frame.
+        if inner.resident() >= inner.capacity {
+            return Err(EvictError::FrameReplacerFull);
+        }
+
+        if inner.b1.contains(&id) {
+            // Ghost hit in `B1`: `T1` should have been larger.
+            let delta = (inner.b2.len() / inner.b1.len()).max(1);
+            inner.p = (inner.p + delta).min(inner.capacity);
+            inner.b1.remove(&id);
+            inner.t2.push_back(id);
+        } else if inner.b2.contains(&id) {
+            // Ghost hit in `B2`: `T2` should have been larger.
+            let delta = (inner.b1.len() / inner.b2.len()).max(1);
+            inner.p = inner.p.saturating_sub(delta);
+            inner.b2.remove(&id);
+            inner.t2.push_back(id);
+        } else {
+            inner.insert_new(id);
+        }
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // ARC does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if !inner.is_resident(&id) {
+            return Err(EvictError::InvalidFrameId(id));
+        }
+        inner.pinned.insert(id);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        if !inner.is_resident(&id) {
+            drop(inner);
+            return self.touch(id);
+        }
+        inner.pinned.remove(&id);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if inner.pinned.contains(&id) {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+
+        // Removed frames are not remembered in ghost lists.
+        if !inner.t1.remove(&id) {
+            inner.t2.remove(&id);
+        }
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().capacity
+    }
+
+    fn size(&self) -> usize {
+        let inner = self.inner.read();
+        inner.resident() - inner.pinned.len()
+    }
+}
@@ -0,0 +1,202 @@
+//! Belady's optimal (OPT) page replacement algorithm.
+
+use {
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    priority_queue::PriorityQueue,
+    std::{
+        cmp::Reverse,
+        collections::{HashMap, VecDeque},
+        sync::Arc,
+    },
+};
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    /// Sequence number of the last access.
+    last_ref: u64,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+/// Belady's optimal (OPT, MIN) frame replacer.
+///
+/// The replacer is built from the complete sequence of future accesses, and
+/// evicts the frame whose next access is the furthest in the future (frames
+/// which are never accessed again go first, the least recently used one
+/// first). This results in the lowest possible number of misses, so it is not
+/// meant for production use, but rather as a baseline, to find how far other
+/// policies are from the optimum on a recorded trace.
+///
+/// Every access to a frame consumes its next occurrence in the sequence, so
+/// the accesses are expected to follow the sequence (registering a frame by
+/// unpinning it is not an access). Frames which do not occur in the sequence
+/// (anymore) are considered to be never accessed again.
+///
+/// Pinned frames are taken out of the queue, but keep consuming their
+/// occurrences when accessed.
+pub struct BeladyReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Maximum number of frames that can be stored in the replacer.
+    capacity: usize,
+
+    /// Positions of the remaining accesses to every frame in the sequence.
+    future: HashMap<F, VecDeque<usize>>,
+
+    /// Mapping of frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    /// Evictable frames, ordered by the position of their next access (and
+    /// then by recency).
+    queue: PriorityQueue<F, (usize, Reverse<u64>)>,
+
+    /// Sequence number of the last access.
+    seq: u64,
+}
+
+impl<F: FrameId> BeladyReplacer<F> {
+    /// Creates a new Belady replacer for the given sequence of accesses.
+    pub fn new(capacity: usize, accesses: impl IntoIterator<Item = F>) -> Self {
+        let mut future: HashMap<F, VecDeque<usize>> = HashMap::new();
+        for (pos, id) in accesses.into_iter().enumerate() {
+            future.entry(id).or_default().push_back(pos);
+        }
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                capacity,
+                future,
+                pages: HashMap::with_capacity(capacity),
+                queue: PriorityQueue::with_capacity(capacity),
+                seq: 0,
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    /// Position of the next access to the frame, `usize::MAX` if there is
+    /// none.
+    fn next_use(&self, id: &F) -> usize {
+        self.future
+            .get(id)
+            .and_then(|positions| positions.front().copied())
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Stores the frame (or updates the stored one) as the most recently used
+    /// one.
+    ///
+    /// The next occurrence of the frame in the sequence is consumed only if
+    /// the frame is actually accessed.
+    fn register(&mut self, id: F, access: bool) -> EvictResult<(), F> {
+        // The replacer is full, cannot add new page.
+        if self.pages.len() >= self.capacity && !self.pages.contains_key(&id) {
+            return Err(EvictError::FrameReplacerFull);
+        }
+
+        // Consume the current access.
+        if access && let Some(positions) = self.future.get_mut(&id) {
+            positions.pop_front();
+            if positions.is_empty() {
+                self.future.remove(&id);
+            }
+        }
+
+        self.seq += 1;
+        let seq = self.seq;
+        let next_use = self.next_use(&id);
+        let page = self.pages.entry(id.clone()).or_insert(PageInfo {
+            last_ref: seq,
+            evictable: true,
+        });
+        page.last_ref = seq;
+        if page.evictable {
+            self.queue.push(id, (next_use, Reverse(seq)));
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for BeladyReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let (id, _) = inner.queue.pop()?;
+        inner.pages.remove(&id);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        self.inner.read().queue.peek().map(|(id, _)| id.clone())
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        self.inner.write().register(id, true)
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // Belady does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let page = inner
+            .pages
+            .get_mut(&id)
+            .ok_or(EvictError::InvalidFrameId(id.clone()))?;
+        page.evictable = false;
+        inner.queue.remove(&id);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+        let next_use = inner.next_use(&id);
+
+        // Unpinning unknown frame registers it, but it is not an access, so the
+        // occurrence of the frame in the sequence is kept.
+        let Some(page) = inner.pages.get_mut(&id) else {
+            return inner.register(id, false);
+        };
+
+        // No-op if the frame is already in the desired state.
+        if std::mem::replace(&mut page.evictable, true) {
+            return Ok(());
+        }
+        let priority = (next_use, Reverse(page.last_ref));
+        inner.queue.push(id, priority);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if let Some(page) = inner.pages.get(&id) {
+            if !page.evictable {
+                return Err(EvictError::PinnedFrameRemoval(id));
+            }
+            inner.pages.remove(&id);
+            inner.queue.remove(&id);
+        }
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().queue.len()
+    }
+}
@@ -0,0 +1,684 @@
+//! CAR and CART page replacement algorithms.
+//!
+//! The algorithms implemented here are based on the [CAR paper](https://www.usenix.org/legacy/events/fast04/tech/full_papers/bansal/bansal.pdf).
+
+use {
+    super::list::{FrameList, QueueView},
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{
+        collections::HashMap,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+    },
+};
+
+/// Configuration of the CAR replacer.
+#[derive(Debug)]
+pub struct CarConfig {
+    /// Maximum number of resident frames.
+    pub capacity: usize,
+
+    /// Whether to use temporal filtering (CART).
+    ///
+    /// With temporal filtering, frames are additionally marked as having
+    /// short-term or long-term utility. Only frames which are re-referenced
+    /// over a long enough period of time are considered long-term ones and
+    /// are protected from eviction, so that correlated references (e.g. the
+    /// ones that come in bursts) do not pollute the frequency clock.
+    pub temporal_filtering: bool,
+}
+
+impl Default for CarConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            temporal_filtering: false,
+        }
+    }
+}
+
+/// Clock a resident page resides in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Clock {
+    T1,
+    T2,
+}
+
+/// Temporal filter bit of a page (used by CART only).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Filter {
+    /// Page with short-term utility.
+    Short,
+
+    /// Page with long-term utility.
+    Long,
+}
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    clock: Clock,
+
+    filter: Filter,
+
+    /// Reference bit, set on every access.
+    ///
+    /// The bit is atomic, so that it can be set while holding a read lock.
+    referenced: AtomicBool,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+/// Clock with Adaptive Replacement (CAR) frame replacer.
+///
+/// CAR keeps the adaptivity of [`ArcReplacer`](crate::ArcReplacer), but
+/// replaces its LRU lists with clocks. Resident frames are split between two
+/// clocks: `T1` holds frames that have been referenced only once recently,
+/// while `T2` holds frames that have been referenced at least twice. Frames
+/// evicted from the clocks are remembered in ghost lists `B1` and `B2`, and
+/// hits in ghost lists adapt the target size `p` of `T1`, the same way as in
+/// ARC.
+///
+/// When a victim is needed, the hand of `T1` (if it exceeds its target size)
+/// or of `T2` sweeps over the frames: referenced frames are moved to the tail
+/// of `T2`, and the first non-referenced frame is evicted.
+///
+/// With [`CarConfig::temporal_filtering`] enabled, the replacer implements
+/// CART: referenced frames are recycled within `T1` until they prove to have
+/// long-term utility, and only such frames can make it into `T2`. An extra
+/// adaptive target bounds the size of `B1`.
+///
+/// Accessing a resident frame only sets its reference bit, so it requires a
+/// read lock only.
+///
+/// Pinned frames stay in their clocks (so they keep their history), but are
+/// skipped by the hands.
+pub struct CarReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer (`c` in the paper is the capacity).
+    config: CarConfig,
+
+    /// Target size of `T1`.
+    p: usize,
+
+    /// Target size of `B1` (used by CART only).
+    q: usize,
+
+    /// Number of resident pages with short-term utility.
+    short: usize,
+
+    /// Number of resident pages with long-term utility.
+    long: usize,
+
+    /// Resident pages referenced once recently, front is the hand position.
+    t1: FrameList<F>,
+
+    /// Resident pages referenced at least twice recently, front is the hand
+    /// position.
+    t2: FrameList<F>,
+
+    /// Ghost pages recently evicted from `T1`.
+    b1: FrameList<F>,
+
+    /// Ghost pages recently evicted from `T2`.
+    b2: FrameList<F>,
+
+    /// Mapping of resident frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    /// Number of evictable pages in `T1`.
+    t1_evictable: usize,
+
+    /// Number of evictable pages in `T2`.
+    t2_evictable: usize,
+}
+
+impl<F: FrameId> Default for CarReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(CarConfig::default())
+    }
+}
+
+impl<F: FrameId> CarReplacer<F> {
+    /// Creates a new CAR replacer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(CarConfig {
+            capacity,
+            ..CarConfig::default()
+        })
+    }
+
+    /// Creates a new CART replacer with the given capacity.
+    pub fn with_temporal_filtering(capacity: usize) -> Self {
+        Self::with_config(CarConfig {
+            capacity,
+            temporal_filtering: true,
+        })
+    }
+
+    /// Creates a new CAR replacer with the given configuration.
+    pub fn with_config(config: CarConfig) -> Self {
+        let capacity = config.capacity;
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                config,
+                p: 0,
+                q: 0,
+                short: 0,
+                long: 0,
+                t1: FrameList::with_capacity(capacity),
+                t2: FrameList::with_capacity(capacity),
+                b1: FrameList::with_capacity(capacity),
+                b2: FrameList::with_capacity(capacity),
+                pages: HashMap::with_capacity(capacity),
+                t1_evictable: 0,
+                t2_evictable: 0,
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    fn clock(&mut self, clock: Clock) -> &mut FrameList<F> {
+        match clock {
+            Clock::T1 => &mut self.t1,
+            Clock::T2 => &mut self.t2,
+        }
+    }
+
+    fn evictable(&mut self, clock: Clock) -> &mut usize {
+        match clock {
+            Clock::T1 => &mut self.t1_evictable,
+            Clock::T2 => &mut self.t2_evictable,
+        }
+    }
+
+    fn filter(&mut self, filter: Filter) -> &mut usize {
+        match filter {
+            Filter::Short => &mut self.short,
+            Filter::Long => &mut self.long,
+        }
+    }
+
+    /// Inserts a new resident page at the tail of the clock.
+    fn insert(&mut self, id: F, clock: Clock, filter: Filter) {
+        self.pages.insert(id.clone(), PageInfo {
+            clock,
+            filter,
+            referenced: AtomicBool::new(false),
+            evictable: true,
+        });
+        self.clock(clock).push_back(id);
+        *self.evictable(clock) += 1;
+        *self.filter(filter) += 1;
+    }
+
+    /// Removes the resident page.
+    fn detach(&mut self, id: &F) -> Option<PageInfo> {
+        let page = self.pages.remove(id)?;
+        self.clock(page.clock).remove(id);
+        if page.evictable {
+            *self.evictable(page.clock) -= 1;
+        }
+        *self.filter(page.filter) -= 1;
+        Some(page)
+    }
+
+    /// Moves the resident page to the tail of the clock, clearing its
+    /// reference bit.
+    fn move_to(&mut self, id: F, clock: Clock) {
+        let Some(page) = self.pages.get_mut(&id) else {
+            return;
+        };
+        *page.referenced.get_mut() = false;
+        let (from, evictable) = (std::mem::replace(&mut page.clock, clock), page.evictable);
+        if evictable {
+            *self.evictable(from) -= 1;
+            *self.evictable(clock) += 1;
+        }
+        self.clock(from).remove(&id);
+        self.clock(clock).push_back(id);
+    }
+
+    /// Moves the hand of the clock one page forward, leaving the page it
+    /// points to intact.
+    fn rotate(&mut self, clock: Clock) {
+        let clock = self.clock(clock);
+        if let Some(id) = clock.pop_front() {
+            clock.push_back(id);
+        }
+    }
+
+    /// Page the hand of the clock points to, along with its state.
+    fn head(&self, clock: Clock) -> Option<(F, &PageInfo)> {
+        let id = match clock {
+            Clock::T1 => self.t1.front(),
+            Clock::T2 => self.t2.front(),
+        }?;
+        self.pages.get(id).map(|page| (id.clone(), page))
+    }
+
+    /// Clock the victim should be taken from.
+    fn target(&self) -> Clock {
+        if (self.t1.len() >= self.p.max(1) && self.t1_evictable > 0) || self.t2_evictable == 0 {
+            Clock::T1
+        } else {
+            Clock::T2
+        }
+    }
+
+    /// Runs the hands until one of them points to a victim.
+    fn advance(&mut self) -> Option<F> {
+        if self.t1_evictable + self.t2_evictable == 0 {
+            return None;
+        }
+        if self.config.temporal_filtering {
+            return self.advance_cart();
+        }
+
+        loop {
+            let clock = self.target();
+            let (id, page) = self.head(clock)?;
+            if !page.evictable {
+                self.rotate(clock);
+                continue;
+            }
+            if !page.referenced.load(Ordering::Relaxed) {
+                return Some(id);
+            }
+            // Referenced page has been accessed at least twice.
+            self.move_to(id, Clock::T2);
+        }
+    }
+
+    /// Runs the hands until one of them points to a victim, recycling
+    /// referenced pages the CART way.
+    fn advance_cart(&mut self) -> Option<F> {
+        let capacity = self.config.capacity;
+        loop {
+            // Referenced pages of `T2` are given another round in `T1`.
+            if let Some((id, page)) = self.head(Clock::T2)
+                && page.referenced.load(Ordering::Relaxed)
+            {
+                self.move_to(id, Clock::T1);
+                self.grow_history_target();
+                continue;
+            }
+
+            if let Some((id, page)) = self.head(Clock::T1) {
+                let filter = page.filter;
+                if page.referenced.load(Ordering::Relaxed) {
+                    self.move_to(id.clone(), Clock::T1);
+                    // Page stayed in `T1` long enough to be considered long-term.
+                    if self.t1.len() >= (self.p + 1).min(self.b1.len()) && filter == Filter::Short {
+                        if let Some(page) = self.pages.get_mut(&id) {
+                            page.filter = Filter::Long;
+                        }
+                        self.short -= 1;
+                        self.long += 1;
+                    }
+                    continue;
+                }
+                if filter == Filter::Long {
+                    self.move_to(id, Clock::T2);
+                    self.q = self
+                        .q
+                        .saturating_sub(1)
+                        .max(capacity.saturating_sub(self.t1.len()));
+                    continue;
+                }
+            }
+
+            let clock = self.target();
+            let (id, page) = self.head(clock)?;
+            if !page.evictable {
+                self.rotate(clock);
+                continue;
+            }
+            return Some(id);
+        }
+    }
+
+    /// Grows the target size of `B1`, if the cache is dominated by pages with
+    /// long-term utility.
+    fn grow_history_target(&mut self) {
+        let capacity = self.config.capacity;
+        if self.t2.len() + self.b2.len() + self.t1.len() >= capacity + self.short {
+            self.q = (self.q + 1).min((2 * capacity).saturating_sub(self.t1.len()));
+        }
+    }
+
+    /// Drops the oldest ghost pages, to make room for a brand new page.
+    fn trim_history(&mut self) {
+        let capacity = self.config.capacity;
+        if self.config.temporal_filtering {
+            while self.b1.len() + self.b2.len() > capacity {
+                if self.b1.len() > self.q || self.b2.is_empty() {
+                    self.b1.pop_front();
+                } else {
+                    self.b2.pop_front();
+                }
+            }
+            return;
+        }
+
+        // Keep directory within bounds: `|T1| + |B1| <= c` and the total size of
+        // all the lists is `<= 2c`.
+        let resident = self.t1.len() + self.t2.len();
+        if self.t1.len() + self.b1.len() >= capacity {
+            self.b1.pop_front();
+        } else if resident + self.b1.len() + self.b2.len() >= 2 * capacity {
+            self.b2.pop_front();
+        }
+    }
+}
+
+/// State of a resident page, as seen by the simulated hands.
+#[derive(Debug, Clone, Copy)]
+struct PageState {
+    clock: Clock,
+    filter: Filter,
+    referenced: bool,
+    evictable: bool,
+}
+
+/// Simulation of the hands, which finds the page they stop at without
+/// modifying the replacer.
+///
+/// Only the state the choice of the victim depends on is simulated, e.g. the
+/// target size of `B1` is not.
+struct Simulation<'a, F: FrameId> {
+    inner: &'a Inner<F>,
+    t1: QueueView<'a, F>,
+    t2: QueueView<'a, F>,
+
+    /// Page states changed by the hands.
+    states: HashMap<F, PageState>,
+
+    t1_evictable: usize,
+    t2_evictable: usize,
+}
+
+impl<'a, F: FrameId> Simulation<'a, F> {
+    fn new(inner: &'a Inner<F>) -> Self {
+        Self {
+            inner,
+            t1: QueueView::new(&inner.t1),
+            t2: QueueView::new(&inner.t2),
+            states: HashMap::new(),
+            t1_evictable: inner.t1_evictable,
+            t2_evictable: inner.t2_evictable,
+        }
+    }
+
+    fn clock(&mut self, clock: Clock) -> &mut QueueView<'a, F> {
+        match clock {
+            Clock::T1 => &mut self.t1,
+            Clock::T2 => &mut self.t2,
+        }
+    }
+
+    fn evictable(&mut self, clock: Clock) -> &mut usize {
+        match clock {
+            Clock::T1 => &mut self.t1_evictable,
+            Clock::T2 => &mut self.t2_evictable,
+        }
+    }
+
+    /// Page the hand of the clock points to, along with its state.
+    fn head(&self, clock: Clock) -> Option<(F, PageState)> {
+        let id = match clock {
+            Clock::T1 => self.t1.front(),
+            Clock::T2 => self.t2.front(),
+        }?;
+        if let Some(state) = self.states.get(id) {
+            return Some((id.clone(), *state));
+        }
+        self.inner.pages.get(id).map(|page| {
+            (id.clone(), PageState {
+                clock: page.clock,
+                filter: page.filter,
+                referenced: page.referenced.load(Ordering::Relaxed),
+                evictable: page.evictable,
+            })
+        })
+    }
+
+    /// Mirrors [`Inner::move_to`], for the page the hand of its clock points
+    /// to.
+    fn move_to(&mut self, id: F, mut state: PageState, clock: Clock) {
+        let from = std::mem::replace(&mut state.clock, clock);
+        state.referenced = false;
+        if state.evictable {
+            *self.evictable(from) -= 1;
+            *self.evictable(clock) += 1;
+        }
+        self.clock(from).pop_front();
+        self.clock(clock).push_back(id.clone());
+        self.states.insert(id, state);
+    }
+
+    /// Mirrors [`Inner::rotate`].
+    fn rotate(&mut self, clock: Clock) {
+        let clock = self.clock(clock);
+        if let Some(id) = clock.pop_front() {
+            clock.push_back(id);
+        }
+    }
+
+    /// Mirrors [`Inner::target`].
+    fn target(&self) -> Clock {
+        if (self.t1.len() >= self.inner.p.max(1) && self.t1_evictable > 0) || self.t2_evictable == 0
+        {
+            Clock::T1
+        } else {
+            Clock::T2
+        }
+    }
+
+    /// Mirrors [`Inner::advance`].
+    fn advance(mut self) -> Option<F> {
+        if self.t1_evictable + self.t2_evictable == 0 {
+            return None;
+        }
+        if self.inner.config.temporal_filtering {
+            return self.advance_cart();
+        }
+
+        loop {
+            let clock = self.target();
+            let (id, state) = self.head(clock)?;
+            if !state.evictable {
+                self.rotate(clock);
+                continue;
+            }
+            if !state.referenced {
+                return Some(id);
+            }
+            self.move_to(id, state, Clock::T2);
+        }
+    }
+
+    /// Mirrors [`Inner::advance_cart`].
+    fn advance_cart(mut self) -> Option<F> {
+        loop {
+            if let Some((id, state)) = self.head(Clock::T2)
+                && state.referenced
+            {
+                self.move_to(id, state, Clock::T1);
+                continue;
+            }
+
+            if let Some((id, mut state)) = self.head(Clock::T1) {
+                if state.referenced {
+                    if self.t1.len() >= (self.inner.p + 1).min(self.inner.b1.len()) {
+                        state.filter = Filter::Long;
+                    }
+                    self.move_to(id, state, Clock::T1);
+                    continue;
+                }
+                if state.filter == Filter::Long {
+                    self.move_to(id, state, Clock::T2);
+                    continue;
+                }
+            }
+
+            let clock = self.target();
+            let (id, state) = self.head(clock)?;
+            if !state.evictable {
+                self.rotate(clock);
+                continue;
+            }
+            return Some(id);
+        }
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for CarReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let id = inner.advance()?;
+
+        // Remember the identifier of the victim in the corresponding ghost list.
+        let page = inner.detach(&id)?;
+        match page.clock {
+            Clock::T1 => inner.b1.push_back(id.clone()),
+            Clock::T2 => inner.b2.push_back(id.clone()),
+        }
+
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        Simulation::new(&self.inner.read()).advance()
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        // Fast path: the page is resident, just set its reference bit.
+        {
+            let inner = self.inner.read();
+            if let Some(page) = inner.pages.get(&id) {
+                page.referenced.store(true, Ordering::Relaxed);
+                return Ok(());
+            }
+        }
+
+        let mut inner = self.inner.write();
+        if let Some(page) = inner.pages.get(&id) {
+            page.referenced.store(true, Ordering::Relaxed);
+            return Ok(());
+        }
+
+        // Cache miss: make sure that there is room for a new resident page.
+        let capacity = inner.config.capacity;
+        if inner.pages.len() >= capacity {
+            return Err(EvictError::FrameReplacerFull);
+        }
+
+        // CART puts pages re-referenced from history into `T1`, where they are
+        // recycled until the next sweep, CAR puts them into `T2` straight away.
+        let filtering = inner.config.temporal_filtering;
+        let clock = if filtering { Clock::T1 } else { Clock::T2 };
+        if inner.b1.contains(&id) {
+            // Ghost hit in `B1`: `T1` should have been larger.
+            let delta = if filtering {
+                inner.short / inner.b1.len()
+            } else {
+                inner.b2.len() / inner.b1.len()
+            };
+            inner.p = (inner.p + delta.max(1)).min(capacity);
+            inner.b1.remove(&id);
+            inner.insert(id, clock, Filter::Long);
+        } else if inner.b2.contains(&id) {
+            // Ghost hit in `B2`: `T2` should have been larger.
+            let delta = if filtering {
+                inner.long / inner.b2.len()
+            } else {
+                inner.b1.len() / inner.b2.len()
+            };
+            inner.p = inner.p.saturating_sub(delta.max(1));
+            inner.b2.remove(&id);
+            inner.insert(id, clock, Filter::Long);
+            if filtering {
+                inner.grow_history_target();
+            }
+        } else {
+            inner.trim_history();
+            inner.insert(id, Clock::T1, Filter::Short);
+        }
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // CAR does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let page = inner
+            .pages
+            .get_mut(&id)
+            .ok_or(EvictError::InvalidFrameId(id))?;
+
+        // No-op if the frame is already in the desired state.
+        if !std::mem::replace(&mut page.evictable, false) {
+            return Ok(());
+        }
+        let clock = page.clock;
+        *inner.evictable(clock) -= 1;
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(page) = inner.pages.get_mut(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+
+        // No-op if the frame is already in the desired state.
+        if std::mem::replace(&mut page.evictable, true) {
+            return Ok(());
+        }
+        let clock = page.clock;
+        *inner.evictable(clock) += 1;
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if inner.pages.get(&id).is_some_and(|page| !page.evictable) {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+
+        // Removed frames are not remembered in ghost lists.
+        inner.detach(&id);
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        let inner = self.inner.read();
+        inner.t1_evictable + inner.t2_evictable
+    }
+}
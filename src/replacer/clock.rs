@@ -0,0 +1,383 @@
+//! Clock (second chance) page replacement algorithm.
+
+use {
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{
+        collections::HashMap,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+    },
+};
+
+/// State of a slot in the clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// No frame is stored in the slot.
+    Empty,
+
+    /// Frame is stored, but is not a candidate for eviction.
+    Pinned,
+
+    /// Frame is stored, and can be evicted.
+    Evictable,
+}
+
+/// Slot of the clock.
+#[derive(Debug)]
+struct Slot {
+    /// Reference bit, set on every access.
+    ///
+    /// The bit is atomic, so that it can be set while holding a read lock.
+    referenced: AtomicBool,
+
+    state: State,
+}
+
+/// Fixed array of slots with a rotating hand.
+///
+/// Shared by both [`ClockReplacer`] and [`DenseClockReplacer`], which differ
+/// only in the way frame IDs are mapped to slots.
+#[derive(Debug)]
+struct Clock {
+    slots: Vec<Slot>,
+
+    /// Position of the clock hand.
+    hand: usize,
+
+    /// Number of evictable frames.
+    size: usize,
+}
+
+impl Clock {
+    fn new(capacity: usize) -> Self {
+        Self {
+            slots: (0..capacity)
+                .map(|_| Slot {
+                    referenced: AtomicBool::new(false),
+                    state: State::Empty,
+                })
+                .collect(),
+            hand: 0,
+            size: 0,
+        }
+    }
+
+    fn state(&self, slot: usize) -> State {
+        self.slots.get(slot).map_or(State::Empty, |s| s.state)
+    }
+
+    /// Sets the reference bit of the slot.
+    fn reference(&self, slot: usize) {
+        self.slots[slot].referenced.store(true, Ordering::Relaxed);
+    }
+
+    /// Stores a new frame in the empty slot.
+    fn insert(&mut self, slot: usize) {
+        let s = &mut self.slots[slot];
+        s.referenced.store(true, Ordering::Relaxed);
+        s.state = State::Evictable;
+        self.size += 1;
+    }
+
+    fn set_state(&mut self, slot: usize, state: State) {
+        let s = &mut self.slots[slot];
+        match (s.state, state) {
+            (State::Evictable, State::Pinned | State::Empty) => self.size -= 1,
+            (State::Pinned | State::Empty, State::Evictable) => self.size += 1,
+            _ => {}
+        }
+        s.state = state;
+    }
+
+    /// Finds the slot the hand stops at, without moving the hand.
+    ///
+    /// The hand stops at the first evictable slot with a cleared reference
+    /// bit. If all evictable slots have their bits set, the hand clears them
+    /// during the first revolution, and stops at the first evictable slot.
+    fn victim(&self) -> Option<usize> {
+        if self.size == 0 {
+            return None;
+        }
+        let n = self.slots.len();
+        let evictable = (0..n)
+            .map(|i| (self.hand + i) % n)
+            .filter(|&slot| self.slots[slot].state == State::Evictable);
+        evictable
+            .clone()
+            .find(|&slot| !self.slots[slot].referenced.load(Ordering::Relaxed))
+            .or_else(|| evictable.clone().next())
+    }
+
+    /// Rotates the hand, clearing reference bits, until a victim is found.
+    ///
+    /// The victim's slot is emptied.
+    fn evict(&mut self) -> Option<usize> {
+        if self.size == 0 {
+            return None;
+        }
+        let n = self.slots.len();
+        loop {
+            let slot = self.hand;
+            self.hand = (self.hand + 1) % n;
+
+            let s = &mut self.slots[slot];
+            if s.state != State::Evictable {
+                continue;
+            }
+            // Give referenced frame a second chance.
+            if std::mem::take(s.referenced.get_mut()) {
+                continue;
+            }
+            self.set_state(slot, State::Empty);
+            return Some(slot);
+        }
+    }
+}
+
+/// Clock (second chance) frame replacer.
+///
+/// Frames are stored in a fixed array of slots, each with a reference bit,
+/// which is set on every access. When a victim is needed, the clock hand
+/// sweeps over the slots: referenced frames get a second chance (their bits
+/// are cleared), and the first non-referenced frame is evicted. This
+/// approximates LRU with amortized O(1) `touch` and `evict`.
+///
+/// Accessing a frame which is already stored only sets its reference bit,
+/// so it requires a read lock only.
+///
+/// If frame IDs are dense indexes `0..capacity`, consider
+/// [`DenseClockReplacer`], which does not need to map frame IDs to slots.
+pub struct ClockReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    clock: Clock,
+
+    /// Mapping of frame IDs to their slots.
+    slots: HashMap<F, usize>,
+
+    /// Frame IDs stored in slots.
+    ids: Vec<Option<F>>,
+
+    /// Empty slots.
+    free: Vec<usize>,
+}
+
+impl<F: FrameId> ClockReplacer<F> {
+    /// Creates a new Clock replacer.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                clock: Clock::new(capacity),
+                slots: HashMap::with_capacity(capacity),
+                ids: vec![None; capacity],
+                // Slots are taken from the back, start with the lowest one.
+                free: (0..capacity).rev().collect(),
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for ClockReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let slot = inner.clock.evict()?;
+        let id = inner.ids[slot].take()?;
+        inner.slots.remove(&id);
+        inner.free.push(slot);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        let inner = self.inner.read();
+        inner
+            .clock
+            .victim()
+            .and_then(|slot| inner.ids[slot].clone())
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        // Fast path: the frame is already stored, just set its reference bit.
+        {
+            let inner = self.inner.read();
+            if let Some(&slot) = inner.slots.get(&id) {
+                inner.clock.reference(slot);
+                return Ok(());
+            }
+        }
+
+        let mut inner = self.inner.write();
+        if let Some(&slot) = inner.slots.get(&id) {
+            inner.clock.reference(slot);
+            return Ok(());
+        }
+
+        let slot = inner.free.pop().ok_or(EvictError::FrameReplacerFull)?;
+        inner.clock.insert(slot);
+        inner.ids[slot] = Some(id.clone());
+        inner.slots.insert(id, slot);
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // Clock does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let slot = *inner.slots.get(&id).ok_or(EvictError::InvalidFrameId(id))?;
+        inner.clock.set_state(slot, State::Pinned);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(&slot) = inner.slots.get(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+        inner.clock.set_state(slot, State::Evictable);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let Some(&slot) = inner.slots.get(&id) else {
+            return Ok(());
+        };
+        if inner.clock.state(slot) == State::Pinned {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+        inner.clock.set_state(slot, State::Empty);
+        inner.ids[slot] = None;
+        inner.slots.remove(&id);
+        inner.free.push(slot);
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().clock.slots.len()
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().clock.size
+    }
+}
+
+/// Clock (second chance) frame replacer for dense frame IDs.
+///
+/// Works exactly as [`ClockReplacer`], but frame IDs are expected to be
+/// indexes `0..capacity`, which are used as slots directly. This avoids
+/// hashing frame IDs on every access, and the replacer can never be full.
+pub struct DenseClockReplacer {
+    inner: Arc<RwLock<Clock>>,
+}
+
+impl DenseClockReplacer {
+    /// Creates a new Clock replacer for frame IDs `0..capacity`.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Clock::new(capacity))),
+        }
+    }
+}
+
+impl EvictionPolicy<usize> for DenseClockReplacer {
+    type Error = EvictError<usize>;
+
+    fn evict(&self) -> Option<usize> {
+        self.inner.write().evict()
+    }
+
+    fn peek(&self) -> Option<usize> {
+        self.inner.read().victim()
+    }
+
+    fn touch(&self, id: usize) -> EvictResult<(), usize> {
+        // Fast path: the frame is already stored, just set its reference bit.
+        {
+            let clock = self.inner.read();
+            match clock.slots.get(id) {
+                None => return Err(EvictError::InvalidFrameId(id)),
+                Some(slot) if slot.state != State::Empty => {
+                    clock.reference(id);
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+
+        let mut clock = self.inner.write();
+        if clock.state(id) == State::Empty {
+            clock.insert(id);
+        } else {
+            clock.reference(id);
+        }
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: usize, _access_type: T) -> EvictResult<(), usize> {
+        // Clock does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: usize) -> EvictResult<(), usize> {
+        let mut clock = self.inner.write();
+
+        if clock.state(id) == State::Empty {
+            return Err(EvictError::InvalidFrameId(id));
+        }
+        clock.set_state(id, State::Pinned);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: usize) -> EvictResult<(), usize> {
+        let mut clock = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        if clock.state(id) == State::Empty {
+            drop(clock);
+            return self.touch(id);
+        }
+        clock.set_state(id, State::Evictable);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: usize) -> EvictResult<(), usize> {
+        let mut clock = self.inner.write();
+
+        match clock.state(id) {
+            State::Empty => {}
+            State::Pinned => return Err(EvictError::PinnedFrameRemoval(id)),
+            State::Evictable => clock.set_state(id, State::Empty),
+        }
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().slots.len()
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().size
+    }
+}
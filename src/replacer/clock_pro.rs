@@ -0,0 +1,714 @@
+//! CLOCK-Pro page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the [CLOCK-Pro paper](https://www.usenix.org/legacy/event/usenix05/tech/general/full_papers/jiang/jiang.pdf).
+
+use {
+    super::list::FrameList,
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{
+        collections::{HashMap, HashSet},
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+    },
+};
+
+/// Status of a page tracked by the replacer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// Resident page with small reuse distance.
+    Hot,
+
+    /// Resident page with large reuse distance.
+    Cold,
+
+    /// Non-resident cold page, which is still in its test period.
+    Test,
+}
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    status: Status,
+
+    /// Whether a resident cold page is in its test period.
+    in_test: bool,
+
+    /// Reference bit, set on every access.
+    ///
+    /// The bit is atomic, so that it can be set while holding a read lock.
+    referenced: AtomicBool,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+impl PageInfo {
+    fn new(status: Status) -> Self {
+        Self {
+            status,
+            in_test: status == Status::Cold,
+            referenced: AtomicBool::new(false),
+            evictable: true,
+        }
+    }
+}
+
+/// Implements the CLOCK-Pro page replacement algorithm.
+///
+/// CLOCK-Pro brings the LIRS idea of using reuse distance (instead of
+/// recency) into Clock. Resident pages are either hot (small reuse distance)
+/// or cold, and recently evicted cold pages are kept as non-resident pages for
+/// the duration of their test period. All pages are kept in a single circular
+/// list, with three hands sweeping over it:
+///
+/// - `HAND_cold` looks for a resident cold page to evict. Referenced cold pages
+///   in their test period are promoted to hot, while other referenced cold
+///   pages start a new test period.
+/// - `HAND_hot` demotes non-referenced hot pages to cold, and terminates test
+///   periods of cold pages it passes.
+/// - `HAND_test` terminates test periods of cold pages, removing the
+///   non-resident ones, so that there are at most `capacity` of them.
+///
+/// The target number of resident cold pages is adapted: it grows every time a
+/// non-resident page is accessed during its test period, and shrinks every
+/// time a test period terminates without an access.
+///
+/// Accessing a resident page only sets its reference bit, so it requires a
+/// read lock only.
+pub struct ClockProReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+/// Page counts the hands keep balanced.
+#[derive(Debug, Clone, Copy)]
+struct Counters {
+    /// Target number of resident cold pages (`m_c` in the paper).
+    cold_target: usize,
+
+    /// Number of hot pages.
+    hot: usize,
+
+    /// Number of resident cold pages.
+    cold: usize,
+
+    /// Number of evictable resident cold pages.
+    cold_evictable: usize,
+}
+
+struct Inner<F: FrameId> {
+    /// Maximum number of resident pages (`m` in the paper).
+    capacity: usize,
+
+    counters: Counters,
+
+    /// Number of non-resident cold pages.
+    test: usize,
+
+    /// Number of evictable frames in the replacer.
+    size: usize,
+
+    /// Mapping of frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    /// Circular list of pages.
+    ///
+    /// The front of the list is where `HAND_hot` points to, so the list head
+    /// (where pages are added) is at the back.
+    clock: FrameList<F>,
+
+    /// Page `HAND_cold` points to.
+    hand_cold: Option<F>,
+
+    /// Page `HAND_test` points to.
+    hand_test: Option<F>,
+}
+
+impl<F: FrameId> ClockProReplacer<F> {
+    /// Creates a new CLOCK-Pro replacer.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                capacity,
+                counters: Counters {
+                    cold_target: 1,
+                    hot: 0,
+                    cold: 0,
+                    cold_evictable: 0,
+                },
+                test: 0,
+                size: 0,
+                pages: HashMap::with_capacity(capacity),
+                clock: FrameList::with_capacity(capacity),
+                hand_cold: None,
+                hand_test: None,
+            })),
+        }
+    }
+}
+
+/// State of a page, as seen by the hands.
+#[derive(Debug, Clone, Copy)]
+struct PageState {
+    status: Status,
+    in_test: bool,
+    referenced: bool,
+    evictable: bool,
+}
+
+/// Circular list of pages the hands sweep over.
+///
+/// Implemented by the replacer itself, which eviction modifies, and by
+/// [`Simulation`], which keeps the changes aside, so that peeking runs the
+/// hands through exactly the same steps as eviction does.
+trait Hands<F: FrameId> {
+    /// Maximum number of resident pages.
+    fn capacity(&self) -> usize;
+
+    /// Number of evictable frames.
+    fn size(&self) -> usize;
+
+    fn counters(&mut self) -> &mut Counters;
+
+    /// Page `HAND_cold` points to.
+    fn hand_cold(&mut self) -> &mut Option<F>;
+
+    /// Page `HAND_hot` points to, i.e. the front of the list.
+    fn hand_hot(&mut self) -> Option<F>;
+
+    /// The page following `id` in the circular list.
+    fn next(&mut self, id: &F) -> Option<F>;
+
+    fn state(&self, id: &F) -> Option<PageState>;
+
+    fn set_state(&mut self, id: &F, state: PageState);
+
+    /// Moves hands pointing to the page forward.
+    fn skip_hands(&mut self, id: &F);
+
+    /// Puts the page at the back of the list, without moving the hands.
+    fn push_back(&mut self, id: F);
+
+    /// Removes the non-resident page, once its test period is over.
+    fn end_test(&mut self, id: &F);
+
+    /// Puts the page at the head of the list.
+    fn move_to_head(&mut self, id: F) {
+        self.skip_hands(&id);
+        self.push_back(id.clone());
+        self.hand_cold().get_or_insert(id);
+    }
+
+    fn shrink_cold_target(&mut self) {
+        let counters = self.counters();
+        counters.cold_target = counters.cold_target.saturating_sub(1).max(1);
+    }
+
+    /// Runs `HAND_hot` until the number of hot pages is within its target.
+    fn balance_hot(&mut self) {
+        let capacity = self.capacity();
+        while self.counters().hot > capacity.saturating_sub(self.counters().cold_target) {
+            self.run_hand_hot();
+        }
+    }
+
+    /// Moves `HAND_hot` one page forward.
+    fn run_hand_hot(&mut self) {
+        let Some(id) = self.hand_hot() else {
+            return;
+        };
+        let Some(mut state) = self.state(&id) else {
+            return;
+        };
+
+        match state.status {
+            Status::Hot => {
+                if !std::mem::take(&mut state.referenced) {
+                    // Demote non-referenced hot page.
+                    state.status = Status::Cold;
+                    state.in_test = false;
+                    let counters = self.counters();
+                    counters.hot -= 1;
+                    counters.cold += 1;
+                    if state.evictable {
+                        counters.cold_evictable += 1;
+                    }
+                }
+            }
+            Status::Cold => {
+                if std::mem::take(&mut state.in_test) {
+                    self.shrink_cold_target();
+                }
+            }
+            Status::Test => {
+                self.end_test(&id);
+                self.shrink_cold_target();
+                return;
+            }
+        }
+
+        // Rotate the list, so that the hand points to the next page.
+        self.set_state(&id, state);
+        self.push_back(id);
+    }
+
+    /// Runs `HAND_cold` until it points to a victim.
+    fn advance(&mut self) -> Option<F> {
+        if self.size() == 0 {
+            return None;
+        }
+
+        loop {
+            // All evictable pages are hot, demote some of them.
+            if self.counters().cold_evictable == 0 {
+                self.run_hand_hot();
+                continue;
+            }
+
+            let id = self.hand_cold().clone()?;
+            let mut state = self.state(&id)?;
+            if state.status != Status::Cold || !state.evictable {
+                *self.hand_cold() = self.next(&id);
+                continue;
+            }
+            if !std::mem::take(&mut state.referenced) {
+                return Some(id);
+            }
+
+            if state.in_test {
+                // Re-referenced during the test period: the page is hot.
+                state.status = Status::Hot;
+                state.in_test = false;
+                let counters = self.counters();
+                counters.cold -= 1;
+                counters.cold_evictable -= 1;
+                counters.hot += 1;
+                self.set_state(&id, state);
+                self.move_to_head(id);
+                self.balance_hot();
+            } else {
+                // Give the page a new test period.
+                state.in_test = true;
+                self.set_state(&id, state);
+                self.move_to_head(id);
+            }
+        }
+    }
+}
+
+impl<F: FrameId> Hands<F> for Inner<F> {
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn counters(&mut self) -> &mut Counters {
+        &mut self.counters
+    }
+
+    fn hand_cold(&mut self) -> &mut Option<F> {
+        &mut self.hand_cold
+    }
+
+    fn hand_hot(&mut self) -> Option<F> {
+        self.clock.front().cloned()
+    }
+
+    fn next(&mut self, id: &F) -> Option<F> {
+        self.clock.next(id).or_else(|| self.clock.front()).cloned()
+    }
+
+    fn state(&self, id: &F) -> Option<PageState> {
+        self.pages.get(id).map(|page| PageState {
+            status: page.status,
+            in_test: page.in_test,
+            referenced: page.referenced.load(Ordering::Relaxed),
+            evictable: page.evictable,
+        })
+    }
+
+    fn set_state(&mut self, id: &F, state: PageState) {
+        if let Some(page) = self.pages.get_mut(id) {
+            page.status = state.status;
+            page.in_test = state.in_test;
+            *page.referenced.get_mut() = state.referenced;
+        }
+    }
+
+    fn skip_hands(&mut self, id: &F) {
+        if self.hand_cold.as_ref() != Some(id) && self.hand_test.as_ref() != Some(id) {
+            return;
+        }
+        let next = self.next(id).filter(|next| next != id);
+        if self.hand_cold.as_ref() == Some(id) {
+            self.hand_cold = next.clone();
+        }
+        if self.hand_test.as_ref() == Some(id) {
+            self.hand_test = next;
+        }
+    }
+
+    fn push_back(&mut self, id: F) {
+        self.clock.push_back(id.clone());
+        self.hand_test.get_or_insert(id);
+    }
+
+    fn end_test(&mut self, id: &F) {
+        self.unlink(id);
+        self.test -= 1;
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    fn is_resident(&self, id: &F) -> bool {
+        self.pages
+            .get(id)
+            .is_some_and(|page| page.status != Status::Test)
+    }
+
+    /// Removes the page from the list.
+    fn unlink(&mut self, id: &F) {
+        self.skip_hands(id);
+        self.clock.remove(id);
+        self.pages.remove(id);
+    }
+
+    fn grow_cold_target(&mut self) {
+        let counters = &mut self.counters;
+        counters.cold_target = (counters.cold_target + 1).min(self.capacity.max(1));
+    }
+
+    /// Runs `HAND_test` until the number of non-resident pages is within the
+    /// limit.
+    fn balance_test(&mut self) {
+        while self.test > self.capacity {
+            self.run_hand_test();
+        }
+    }
+
+    /// Moves `HAND_test` one page forward.
+    fn run_hand_test(&mut self) {
+        let Some(id) = self.hand_test.clone() else {
+            return;
+        };
+        self.hand_test = self.next(&id);
+        let Some(page) = self.pages.get_mut(&id) else {
+            return;
+        };
+
+        match page.status {
+            Status::Hot => {}
+            Status::Cold => {
+                if std::mem::take(&mut page.in_test) {
+                    self.shrink_cold_target();
+                }
+            }
+            Status::Test => {
+                self.end_test(&id);
+                self.shrink_cold_target();
+            }
+        }
+    }
+
+    /// Evicts the page `HAND_cold` points to.
+    fn reclaim(&mut self, id: &F) {
+        let Some(page) = self.pages.get_mut(id) else {
+            return;
+        };
+        self.counters.cold -= 1;
+        self.counters.cold_evictable -= 1;
+        self.size -= 1;
+
+        if page.in_test {
+            // Keep the page as a non-resident one, till its test period is over.
+            page.status = Status::Test;
+            self.hand_cold = self.next(id);
+            self.test += 1;
+            self.balance_test();
+        } else {
+            self.unlink(id);
+        }
+    }
+}
+
+/// Simulation of the hands, which finds the page `HAND_cold` stops at
+/// without modifying the replacer.
+///
+/// Changes the hands would make are kept aside: pages the hands would move to
+/// the head of the list (or rotate past `HAND_hot`) are appended to `moved`,
+/// while the rest of the list keeps its stored order. `HAND_test` does not
+/// move while looking for a victim, so it is not simulated.
+struct Simulation<'a, F: FrameId> {
+    inner: &'a Inner<F>,
+
+    /// Page states changed by the hands.
+    states: HashMap<F, PageState>,
+
+    /// Pages moved to the head of the list, in order. Entries of pages which
+    /// were moved again later are stale.
+    moved: Vec<F>,
+
+    /// Positions of the latest entries of the pages in `moved`.
+    positions: HashMap<F, usize>,
+
+    /// Non-resident pages removed from the list.
+    removed: HashSet<F>,
+
+    /// Front of the part of the list which keeps its stored order.
+    front: Option<F>,
+
+    /// Position of the first entry in `moved` which may not be stale.
+    head: usize,
+
+    hand_cold: Option<F>,
+    counters: Counters,
+}
+
+impl<'a, F: FrameId> Simulation<'a, F> {
+    fn new(inner: &'a Inner<F>) -> Self {
+        Self {
+            inner,
+            states: HashMap::new(),
+            moved: Vec::new(),
+            positions: HashMap::new(),
+            removed: HashSet::new(),
+            front: inner.clock.front().cloned(),
+            head: 0,
+            hand_cold: inner.hand_cold.clone(),
+            counters: inner.counters,
+        }
+    }
+
+    /// Whether the page is still at its stored position in the list.
+    fn is_unmoved(&self, id: &F) -> bool {
+        !self.positions.contains_key(id) && !self.removed.contains(id)
+    }
+
+    /// The first non-stale entry in `moved`, starting from `position`
+    /// (inclusive).
+    fn moved_from(&self, position: usize) -> Option<usize> {
+        (position..self.moved.len()).find(|&i| self.positions.get(&self.moved[i]) == Some(&i))
+    }
+}
+
+impl<F: FrameId> Hands<F> for Simulation<'_, F> {
+    fn capacity(&self) -> usize {
+        self.inner.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.size
+    }
+
+    fn counters(&mut self) -> &mut Counters {
+        &mut self.counters
+    }
+
+    fn hand_cold(&mut self) -> &mut Option<F> {
+        &mut self.hand_cold
+    }
+
+    fn hand_hot(&mut self) -> Option<F> {
+        while let Some(id) = self.front.take() {
+            if self.is_unmoved(&id) {
+                self.front = Some(id.clone());
+                return Some(id);
+            }
+            self.front = self.inner.clock.next(&id).cloned();
+        }
+        self.head = self.moved_from(self.head).unwrap_or(self.moved.len());
+        self.moved.get(self.head).cloned()
+    }
+
+    fn next(&mut self, id: &F) -> Option<F> {
+        let next = match self.positions.get(id) {
+            Some(&position) => self.moved_from(position + 1).map(|i| self.moved[i].clone()),
+            None => {
+                let mut next = self.inner.clock.next(id);
+                while let Some(page) = next.filter(|page| !self.is_unmoved(page)) {
+                    next = self.inner.clock.next(page);
+                }
+                next.cloned()
+                    .or_else(|| self.moved_from(self.head).map(|i| self.moved[i].clone()))
+            }
+        };
+        next.or_else(|| self.hand_hot())
+    }
+
+    fn state(&self, id: &F) -> Option<PageState> {
+        if let Some(state) = self.states.get(id) {
+            return Some(*state);
+        }
+        if self.removed.contains(id) {
+            return None;
+        }
+        self.inner.state(id)
+    }
+
+    fn set_state(&mut self, id: &F, state: PageState) {
+        self.states.insert(id.clone(), state);
+    }
+
+    fn skip_hands(&mut self, id: &F) {
+        if self.hand_cold.as_ref() == Some(id) {
+            self.hand_cold = self.next(id).filter(|next| next != id);
+        }
+    }
+
+    fn push_back(&mut self, id: F) {
+        self.positions.insert(id.clone(), self.moved.len());
+        self.moved.push(id);
+    }
+
+    fn end_test(&mut self, id: &F) {
+        self.skip_hands(id);
+        self.positions.remove(id);
+        self.removed.insert(id.clone());
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for ClockProReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let id = inner.advance()?;
+        inner.reclaim(&id);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        Simulation::new(&self.inner.read()).advance()
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        // Fast path: the page is resident, just set its reference bit.
+        {
+            let inner = self.inner.read();
+            if let Some(page) = inner.pages.get(&id)
+                && page.status != Status::Test
+            {
+                page.referenced.store(true, Ordering::Relaxed);
+                return Ok(());
+            }
+        }
+
+        let mut inner = self.inner.write();
+        let status = inner.pages.get(&id).map(|page| page.status);
+        match status {
+            Some(Status::Hot | Status::Cold) => {
+                if let Some(page) = inner.pages.get(&id) {
+                    page.referenced.store(true, Ordering::Relaxed);
+                }
+                return Ok(());
+            }
+            _ if inner.counters.hot + inner.counters.cold >= inner.capacity => {
+                return Err(EvictError::FrameReplacerFull);
+            }
+            Some(Status::Test) => {
+                // Accessed during the test period: the cold area should have been
+                // larger, and the page is hot.
+                inner.grow_cold_target();
+                inner.test -= 1;
+                inner.counters.hot += 1;
+                inner.pages.insert(id.clone(), PageInfo::new(Status::Hot));
+                inner.move_to_head(id);
+                inner.balance_hot();
+            }
+            None => {
+                inner.counters.cold += 1;
+                inner.counters.cold_evictable += 1;
+                inner.pages.insert(id.clone(), PageInfo::new(Status::Cold));
+                inner.move_to_head(id);
+            }
+        }
+        inner.size += 1;
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // CLOCK-Pro does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if !inner.is_resident(&id) {
+            return Err(EvictError::InvalidFrameId(id));
+        }
+        let Some(page) = inner.pages.get_mut(&id) else {
+            return Ok(());
+        };
+
+        // No-op if the frame is already in the desired state.
+        if !std::mem::replace(&mut page.evictable, false) {
+            return Ok(());
+        }
+        if page.status == Status::Cold {
+            inner.counters.cold_evictable -= 1;
+        }
+        inner.size -= 1;
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        if !inner.is_resident(&id) {
+            drop(inner);
+            return self.touch(id);
+        }
+        let Some(page) = inner.pages.get_mut(&id) else {
+            return Ok(());
+        };
+
+        // No-op if the frame is already in the desired state.
+        if std::mem::replace(&mut page.evictable, true) {
+            return Ok(());
+        }
+        if page.status == Status::Cold {
+            inner.counters.cold_evictable += 1;
+        }
+        inner.size += 1;
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let Some(page) = inner.pages.get(&id) else {
+            return Ok(());
+        };
+        match (page.status, page.evictable) {
+            (Status::Test, _) => return Ok(()),
+            (_, false) => return Err(EvictError::PinnedFrameRemoval(id)),
+            (Status::Hot, _) => inner.counters.hot -= 1,
+            (Status::Cold, _) => {
+                inner.counters.cold -= 1;
+                inner.counters.cold_evictable -= 1;
+            }
+        }
+        inner.size -= 1;
+        inner.unlink(&id);
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().size
+    }
+}
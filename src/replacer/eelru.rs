@@ -0,0 +1,439 @@
+//! EELRU page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the "EELRU: Simple and Effective
+//! Adaptive Page Replacement" paper by Smaragdakis et al.
+
+use {
+    super::list::FrameList,
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{collections::HashMap, sync::Arc},
+};
+
+/// Configuration of the EELRU replacer.
+#[derive(Debug)]
+pub struct EelruConfig {
+    /// Maximum number of resident frames.
+    pub capacity: usize,
+
+    /// Early eviction point (`e` in the EELRU paper), relative to the
+    /// capacity. In early eviction mode, the victim is taken from this
+    /// position in the recency order, rather than from its end.
+    pub early_ratio: f64,
+
+    /// Late eviction point (`l` in the EELRU paper), relative to the capacity.
+    /// Recency of the evicted frames is tracked up to this position, to detect
+    /// loops larger than the replacer.
+    pub late_ratio: f64,
+}
+
+impl Default for EelruConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            early_ratio: 0.5,
+            late_ratio: 2.0,
+        }
+    }
+}
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    /// Whether the page is resident, or is just remembered in the recency
+    /// order.
+    resident: bool,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+/// Recency order of the pages, with positions available in O(log n).
+///
+/// Every access stamps the page with the next slot, so slots grow with
+/// recency. The slots are counted in a Fenwick tree, so that the recency
+/// position of a page (the number of pages accessed after it) and the page at
+/// a given position can be found without walking the order. Once the slots
+/// run out, the pages are renumbered from zero.
+#[derive(Debug)]
+struct Recency<F: FrameId> {
+    /// Fenwick tree counting the occupied slots.
+    tree: Vec<usize>,
+
+    /// Pages stamped with the slots.
+    ids: Vec<Option<F>>,
+
+    /// Mapping of page IDs to their slots.
+    slots: HashMap<F, usize>,
+
+    /// Next slot to stamp a page with.
+    next: usize,
+}
+
+impl<F: FrameId> Recency<F> {
+    fn new(limit: usize) -> Self {
+        // Twice as many slots as pages, so that renumbering is amortized.
+        let width = 2 * limit.max(1);
+        Self {
+            tree: vec![0; width],
+            ids: vec![None; width],
+            slots: HashMap::with_capacity(limit),
+            next: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn update(&mut self, slot: usize, occupied: bool) {
+        let mut i = slot + 1;
+        while i <= self.tree.len() {
+            if occupied {
+                self.tree[i - 1] += 1;
+            } else {
+                self.tree[i - 1] -= 1;
+            }
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// Number of occupied slots below the given one.
+    fn prefix(&self, slot: usize) -> usize {
+        let mut i = slot;
+        let mut count = 0;
+        while i > 0 {
+            count += self.tree[i - 1];
+            i &= i - 1;
+        }
+        count
+    }
+
+    /// Moves the page to the most recent position.
+    fn push(&mut self, id: F) {
+        self.remove(&id);
+        if self.next == self.ids.len() {
+            self.renumber();
+        }
+        let slot = self.next;
+        self.next += 1;
+        self.update(slot, true);
+        self.ids[slot] = Some(id.clone());
+        self.slots.insert(id, slot);
+    }
+
+    fn remove(&mut self, id: &F) {
+        if let Some(slot) = self.slots.remove(id) {
+            self.ids[slot] = None;
+            self.update(slot, false);
+        }
+    }
+
+    /// Stamps the pages with slots from zero, keeping their order.
+    fn renumber(&mut self) {
+        let ids = std::mem::take(&mut self.ids);
+        self.tree.iter_mut().for_each(|count| *count = 0);
+        self.ids = vec![None; ids.len()];
+        self.slots.clear();
+        self.next = 0;
+        for id in ids.into_iter().flatten() {
+            self.push(id);
+        }
+    }
+
+    /// Recency position of the page, 0 for the most recently accessed one.
+    fn position(&self, id: &F) -> Option<usize> {
+        let slot = *self.slots.get(id)?;
+        Some(self.len() - self.prefix(slot) - 1)
+    }
+
+    /// The page at the given recency position.
+    fn get(&self, position: usize) -> Option<&F> {
+        // Find the slot with exactly `rank` occupied slots below it.
+        let mut rank = self.len().checked_sub(position + 1)?;
+        let mut slot = 0;
+        let mut step = self.tree.len().next_power_of_two();
+        while step > 0 {
+            if slot + step <= self.tree.len() && self.tree[slot + step - 1] <= rank {
+                slot += step;
+                rank -= self.tree[slot - 1];
+            }
+            step /= 2;
+        }
+        self.ids.get(slot)?.as_ref()
+    }
+}
+
+/// Early Eviction LRU (EELRU) frame replacer.
+///
+/// Works as LRU, unless it detects a loop over more frames than the replacer
+/// can hold, in which case LRU evicts every frame right before it is accessed
+/// again. Then, EELRU switches to early eviction: the victim is taken from the
+/// early eviction point `e` of the recency order (see
+/// [`EelruConfig::early_ratio`]), so that the frames older than `e` stay
+/// resident long enough to be accessed again.
+///
+/// To decide between the modes, recency order is kept for both resident and
+/// recently evicted frames, up to the late eviction point `l` (see
+/// [`EelruConfig::late_ratio`]), and hits are counted by the recency position
+/// of the accessed frame: before `e`, between `e` and the capacity `M`, and
+/// between `M` and `l`. LRU keeps all the hits before `M`, while early
+/// eviction keeps all the hits before `e`, and a `(M - e) / (l - e)` fraction
+/// of the hits between `e` and `l`. The mode with more expected hits is used.
+/// Hit counts are halved every `l` accesses, so that the replacer adapts to
+/// changing access patterns.
+///
+/// Pinned frames keep their positions in the recency order, but are skipped
+/// on eviction.
+pub struct EelruReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: EelruConfig,
+
+    /// Early eviction point.
+    early: usize,
+
+    /// Late eviction point.
+    late: usize,
+
+    /// Mapping of both resident and remembered frame IDs to their page
+    /// information.
+    pages: HashMap<F, PageInfo>,
+
+    /// Recency order of both resident and remembered pages.
+    recency: Recency<F>,
+
+    /// Resident pages in LRU order, the least recently used page is at the
+    /// front.
+    resident: FrameList<F>,
+
+    /// Remembered (evicted) pages in the order of eviction.
+    history: FrameList<F>,
+
+    /// Number of hits before the early eviction point, before the capacity,
+    /// and before the late eviction point.
+    hits: [u64; 3],
+
+    /// Number of accesses since the hit counts were halved.
+    accesses: usize,
+
+    /// Number of evictable frames in the replacer.
+    size: usize,
+}
+
+impl<F: FrameId> Default for EelruReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(EelruConfig::default())
+    }
+}
+
+impl<F: FrameId> EelruReplacer<F> {
+    /// Creates a new EELRU replacer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(EelruConfig {
+            capacity,
+            ..EelruConfig::default()
+        })
+    }
+
+    /// Creates a new EELRU replacer with the given configuration.
+    pub fn with_config(config: EelruConfig) -> Self {
+        let capacity = config.capacity;
+        let early =
+            ((capacity as f64 * config.early_ratio) as usize).min(capacity.saturating_sub(1));
+        let late = ((capacity as f64 * config.late_ratio) as usize).max(capacity + 1);
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                config,
+                early,
+                late,
+                pages: HashMap::with_capacity(late),
+                recency: Recency::new(late),
+                resident: FrameList::with_capacity(capacity),
+                history: FrameList::with_capacity(late - capacity),
+                hits: [0; 3],
+                accesses: 0,
+                size: 0,
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    /// Whether early eviction is expected to result in more hits than LRU.
+    fn early_eviction(&self) -> bool {
+        let [early, lru, late] = self.hits.map(|hits| hits as f64);
+        let capacity = self.config.capacity as f64;
+        let fraction = (capacity - self.early as f64) / (self.late - self.early) as f64;
+        early + (lru + late) * fraction > early + lru
+    }
+
+    fn evictable(&self, id: &F) -> bool {
+        self.pages
+            .get(id)
+            .is_some_and(|page| page.resident && page.evictable)
+    }
+
+    fn victim(&self) -> Option<F> {
+        if self.size == 0 {
+            return None;
+        }
+        if self.early_eviction() {
+            // The first evictable page at or past the early eviction point.
+            let victim = (self.early..self.recency.len())
+                .filter_map(|position| self.recency.get(position))
+                .find(|id| self.evictable(id));
+            if let Some(id) = victim {
+                return Some(id.clone());
+            }
+        }
+        self.resident.iter().find(|id| self.evictable(id)).cloned()
+    }
+
+    /// Counts the hit at the given recency position.
+    fn hit(&mut self, position: usize) {
+        if position < self.early {
+            self.hits[0] += 1;
+        } else if position < self.config.capacity {
+            self.hits[1] += 1;
+        } else if position < self.late {
+            self.hits[2] += 1;
+        }
+    }
+
+    /// Remembers the evicted page, forgetting the least recently evicted ones,
+    /// if there are too many.
+    fn remember(&mut self, id: F) {
+        if self.history.len() >= self.late - self.config.capacity
+            && let Some(oldest) = self.history.pop_front()
+        {
+            self.pages.remove(&oldest);
+            self.recency.remove(&oldest);
+        }
+        self.history.push_back(id);
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for EelruReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let id = inner.victim()?;
+        if let Some(page) = inner.pages.get_mut(&id) {
+            page.resident = false;
+        }
+        inner.resident.remove(&id);
+        inner.size -= 1;
+        inner.remember(id.clone());
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        self.inner.read().victim()
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let resident = inner.pages.get(&id).is_some_and(|page| page.resident);
+        if !resident && inner.resident.len() >= inner.config.capacity {
+            return Err(EvictError::FrameReplacerFull);
+        }
+
+        if let Some(position) = inner.recency.position(&id) {
+            inner.hit(position);
+        }
+        inner.accesses += 1;
+        if inner.accesses >= inner.late {
+            inner.accesses = 0;
+            inner.hits.iter_mut().for_each(|hits| *hits /= 2);
+        }
+
+        if !resident {
+            inner.history.remove(&id);
+            let page = inner.pages.entry(id.clone()).or_insert(PageInfo {
+                resident: true,
+                evictable: true,
+            });
+            page.resident = true;
+            page.evictable = true;
+            inner.size += 1;
+        }
+        inner.recency.push(id.clone());
+        inner.resident.push_back(id);
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // EELRU does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let page = inner
+            .pages
+            .get_mut(&id)
+            .filter(|page| page.resident)
+            .ok_or(EvictError::InvalidFrameId(id))?;
+
+        // No-op if the frame is already in the desired state.
+        if !std::mem::replace(&mut page.evictable, false) {
+            return Ok(());
+        }
+        inner.size -= 1;
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(page) = inner.pages.get_mut(&id).filter(|page| page.resident) else {
+            drop(inner);
+            return self.touch(id);
+        };
+
+        // No-op if the frame is already in the desired state.
+        if std::mem::replace(&mut page.evictable, true) {
+            return Ok(());
+        }
+        inner.size += 1;
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let Some(page) = inner.pages.get(&id).filter(|page| page.resident) else {
+            return Ok(());
+        };
+        if !page.evictable {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+
+        // Removed frames are not remembered.
+        inner.pages.remove(&id);
+        inner.recency.remove(&id);
+        inner.resident.remove(&id);
+        inner.size -= 1;
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().size
+    }
+}
@@ -0,0 +1,402 @@
+//! FBR page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the "Data Cache Management
+//! Using Frequency-Based Replacement" paper by Robinson and Devarakonda.
+
+use {
+    super::list::FrameList,
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    priority_queue::PriorityQueue,
+    std::{cmp::Reverse, collections::HashMap, sync::Arc},
+};
+
+/// Configuration of the FBR replacer.
+#[derive(Debug)]
+pub struct FbrConfig {
+    /// Maximum number of frames that can be stored in the replacer.
+    pub capacity: usize,
+
+    /// Size of the new section, relative to the capacity.
+    ///
+    /// References to frames in the new section are considered correlated, and
+    /// do not increment their reference counts.
+    pub new_ratio: f64,
+
+    /// Size of the old section, relative to the capacity.
+    ///
+    /// Victims are picked from the old section, the larger it is, the more
+    /// frequency matters (compared to recency).
+    pub old_ratio: f64,
+
+    /// Maximum average reference count, once it is exceeded, the reference
+    /// counts of all the frames are halved.
+    pub max_average_count: u64,
+}
+
+impl Default for FbrConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            new_ratio: 0.25,
+            old_ratio: 0.5,
+            max_average_count: 100,
+        }
+    }
+}
+
+/// Section of the LRU stack a page resides in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    New,
+    Middle,
+    Old,
+}
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    section: Section,
+
+    /// Reference count, not incremented while the page is in the new section.
+    count: u64,
+
+    /// Sequence number of the last access.
+    last_ref: u64,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+impl PageInfo {
+    /// Priority of the page in the queue of its section: the lowest reference
+    /// count first, and then the least recently used.
+    fn priority(&self) -> Reverse<(u64, u64)> {
+        Reverse((self.count, self.last_ref))
+    }
+}
+
+/// Frequency-Based Replacement (FBR) frame replacer.
+///
+/// Frames are kept in an LRU stack, divided into three sections: the new
+/// section at the top of the stack (see [`FbrConfig::new_ratio`]), the old
+/// section at the bottom of it (see [`FbrConfig::old_ratio`]), and the middle
+/// section in between. Every frame has a reference count, which is incremented
+/// on access, unless the frame is in the new section: references repeated
+/// shortly after each other are likely to be correlated, and should not make
+/// the frame look frequently used. Any access moves the frame to the top of
+/// the stack.
+///
+/// The frame with the lowest reference count in the old section is evicted
+/// first (the least recently used one, if there is a tie). The middle section
+/// gives frames which left the new section time to build up their counts,
+/// before they become eligible for eviction. If the old section has no
+/// evictable frames, the victim is picked from the middle section, and then
+/// from the new one, the same way.
+///
+/// Once the average reference count exceeds the maximum (see
+/// [`FbrConfig::max_average_count`]), the counts of all the frames are
+/// halved, so that frames which were used frequently in the past do not stay
+/// forever.
+///
+/// Unlike [`LruKConfig::ref_period`](crate::LruKConfig::ref_period), which
+/// factors out correlated references by the time elapsed since the last one,
+/// FBR does that by the position of the frame in the LRU stack.
+///
+/// Evictable frames of every section are kept in a priority queue, ordered by
+/// their reference counts (and then by recency), so that the victim is found
+/// without scanning the section.
+///
+/// Pinned frames keep their positions in the stack, but are never picked as
+/// victims.
+pub struct FbrReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: FbrConfig,
+
+    /// Size of the new section.
+    new_limit: usize,
+
+    /// Size of the middle section.
+    middle_limit: usize,
+
+    /// Sections of the LRU stack, the least recently used frame of each is
+    /// at the front.
+    new: FrameList<F>,
+    middle: FrameList<F>,
+    old: FrameList<F>,
+
+    /// Evictable frames of every section (indexed by [`Section`]), ordered by
+    /// their priorities (see [`PageInfo::priority`]).
+    queues: [PriorityQueue<F, Reverse<(u64, u64)>>; 3],
+
+    /// Mapping of frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    /// Sum of reference counts of all the frames.
+    total_count: u64,
+
+    /// Sequence number of the last access.
+    seq: u64,
+}
+
+impl<F: FrameId> Default for FbrReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(FbrConfig::default())
+    }
+}
+
+impl<F: FrameId> FbrReplacer<F> {
+    /// Creates a new FBR replacer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(FbrConfig {
+            capacity,
+            ..FbrConfig::default()
+        })
+    }
+
+    /// Creates a new FBR replacer with the given configuration.
+    pub fn with_config(config: FbrConfig) -> Self {
+        let capacity = config.capacity;
+        let new_limit = ((capacity as f64 * config.new_ratio) as usize).max(1);
+        let old_limit = (capacity as f64 * config.old_ratio) as usize;
+        let middle_limit = capacity.saturating_sub(new_limit + old_limit);
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                config,
+                new_limit,
+                middle_limit,
+                new: FrameList::with_capacity(new_limit),
+                middle: FrameList::with_capacity(middle_limit),
+                old: FrameList::with_capacity(capacity),
+                queues: Default::default(),
+                pages: HashMap::with_capacity(capacity),
+                total_count: 0,
+                seq: 0,
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    fn section(&mut self, section: Section) -> &mut FrameList<F> {
+        match section {
+            Section::New => &mut self.new,
+            Section::Middle => &mut self.middle,
+            Section::Old => &mut self.old,
+        }
+    }
+
+    fn queue(&mut self, section: Section) -> &mut PriorityQueue<F, Reverse<(u64, u64)>> {
+        &mut self.queues[section as usize]
+    }
+
+    /// Assigns the frame to the section, moving it to the queue of the section
+    /// (if it is evictable).
+    fn assign(&mut self, id: &F, section: Section) {
+        let Some(page) = self.pages.get_mut(id) else {
+            return;
+        };
+        let from = std::mem::replace(&mut page.section, section);
+        if let Some((id, priority)) = self.queue(from).remove(id) {
+            self.queue(section).push(id, priority);
+        }
+    }
+
+    /// Moves the least recently used frame of the section to the top of the
+    /// next one.
+    fn demote(&mut self, from: Section, to: Section) {
+        let Some(id) = self.section(from).pop_front() else {
+            return;
+        };
+        self.assign(&id, to);
+        self.section(to).push_back(id);
+    }
+
+    /// Moves the most recently used frame of the section to the bottom of the
+    /// previous one.
+    fn promote(&mut self, from: Section, to: Section) {
+        let Some(id) = self.section(from).pop_back() else {
+            return;
+        };
+        self.assign(&id, to);
+        self.section(to).push_front(id);
+    }
+
+    /// Moves the section boundaries, so that the sections have their sizes
+    /// again, after frames are added to or removed from the stack.
+    fn rebalance(&mut self) {
+        while self.new.len() > self.new_limit {
+            self.demote(Section::New, Section::Middle);
+        }
+        while self.new.len() < self.new_limit && !self.middle.is_empty() {
+            self.promote(Section::Middle, Section::New);
+        }
+        while self.middle.len() > self.middle_limit {
+            self.demote(Section::Middle, Section::Old);
+        }
+        while self.middle.len() < self.middle_limit && !self.old.is_empty() {
+            self.promote(Section::Old, Section::Middle);
+        }
+    }
+
+    /// Halves reference counts, if the average count is too high.
+    fn age(&mut self) {
+        let limit = self.config.max_average_count.max(1);
+        if self.total_count <= limit.saturating_mul(self.pages.len() as u64) {
+            return;
+        }
+        self.total_count = 0;
+        for (id, page) in &mut self.pages {
+            page.count = page.count.div_ceil(2);
+            self.total_count += page.count;
+            self.queues[page.section as usize].change_priority(id, page.priority());
+        }
+    }
+
+    /// Finds the evictable frame with the lowest reference count in the
+    /// oldest section which has evictable frames.
+    fn victim(&self) -> Option<F> {
+        [Section::Old, Section::Middle, Section::New]
+            .into_iter()
+            .find_map(|section| self.queues[section as usize].peek())
+            .map(|(id, _)| id.clone())
+    }
+
+    /// Removes the frame from the stack.
+    fn unlink(&mut self, id: &F) {
+        let Some(page) = self.pages.remove(id) else {
+            return;
+        };
+        self.section(page.section).remove(id);
+        self.queue(page.section).remove(id);
+        self.total_count -= page.count;
+        self.rebalance();
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for FbrReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let id = inner.victim()?;
+        inner.unlink(&id);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        self.inner.read().victim()
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+        inner.seq += 1;
+        let seq = inner.seq;
+
+        match inner.pages.get_mut(&id) {
+            Some(page) => {
+                let section = std::mem::replace(&mut page.section, Section::New);
+                // References in the new section are correlated, they are not counted.
+                let counted = u64::from(section != Section::New);
+                page.count += counted;
+                page.last_ref = seq;
+                let priority = page.evictable.then(|| page.priority());
+                inner.total_count += counted;
+                inner.section(section).remove(&id);
+                inner.queue(section).remove(&id);
+                if let Some(priority) = priority {
+                    inner.queue(Section::New).push(id.clone(), priority);
+                }
+            }
+            None => {
+                if inner.pages.len() >= inner.config.capacity {
+                    return Err(EvictError::FrameReplacerFull);
+                }
+                let page = PageInfo {
+                    section: Section::New,
+                    count: 1,
+                    last_ref: seq,
+                    evictable: true,
+                };
+                inner.queue(Section::New).push(id.clone(), page.priority());
+                inner.pages.insert(id.clone(), page);
+                inner.total_count += 1;
+            }
+        }
+        inner.new.push_back(id);
+        inner.rebalance();
+        inner.age();
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // FBR does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let page = inner
+            .pages
+            .get_mut(&id)
+            .ok_or(EvictError::InvalidFrameId(id.clone()))?;
+
+        // No-op if the frame is already in the desired state.
+        if !std::mem::replace(&mut page.evictable, false) {
+            return Ok(());
+        }
+        let section = page.section;
+        inner.queue(section).remove(&id);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(page) = inner.pages.get_mut(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+
+        // No-op if the frame is already in the desired state.
+        if std::mem::replace(&mut page.evictable, true) {
+            return Ok(());
+        }
+        let (section, priority) = (page.section, page.priority());
+        inner.queue(section).push(id, priority);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if inner.pages.get(&id).is_some_and(|page| !page.evictable) {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+        inner.unlink(&id);
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner
+            .read()
+            .queues
+            .iter()
+            .map(PriorityQueue::len)
+            .sum()
+    }
+}
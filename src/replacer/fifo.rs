@@ -0,0 +1,131 @@
+//! FIFO page replacement algorithm.
+
+use {
+    super::list::FrameList,
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{collections::HashSet, sync::Arc},
+};
+
+/// First In First Out (FIFO) frame replacer.
+///
+/// Frames are evicted in the order they were added to the replacer. Accessing
+/// a frame does not affect the eviction order.
+///
+/// Pinned frames keep their positions in the queue, but are never picked as
+/// victims.
+pub struct FifoReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Maximum number of frames that can be stored in the replacer.
+    capacity: usize,
+
+    /// Frames in the order of their arrival, the oldest frame is at the front.
+    queue: FrameList<F>,
+
+    /// Frames which are not candidates for eviction.
+    pinned: HashSet<F>,
+}
+
+impl<F: FrameId> FifoReplacer<F> {
+    /// Creates a new FIFO replacer.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                capacity,
+                queue: FrameList::with_capacity(capacity),
+                pinned: HashSet::new(),
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    fn victim(&self) -> Option<F> {
+        self.queue
+            .iter()
+            .find(|id| !self.pinned.contains(*id))
+            .cloned()
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for FifoReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let id = inner.victim()?;
+        inner.queue.remove(&id);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        self.inner.read().victim()
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Accesses do not affect the eviction order.
+        if inner.queue.contains(&id) {
+            return Ok(());
+        }
+        if inner.queue.len() >= inner.capacity {
+            return Err(EvictError::FrameReplacerFull);
+        }
+        inner.queue.push_back(id);
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // FIFO does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if !inner.queue.contains(&id) {
+            return Err(EvictError::InvalidFrameId(id));
+        }
+        inner.pinned.insert(id);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        if !inner.queue.contains(&id) {
+            drop(inner);
+            return self.touch(id);
+        }
+        inner.pinned.remove(&id);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if inner.pinned.contains(&id) {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+        inner.queue.remove(&id);
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().capacity
+    }
+
+    fn size(&self) -> usize {
+        let inner = self.inner.read();
+        inner.queue.len() - inner.pinned.len()
+    }
+}
@@ -0,0 +1,317 @@
+//! GCLOCK (generalized Clock) page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the "Principles of Database
+//! Buffer Management" paper by Effelsberg and Härder.
+
+use {
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{
+        collections::HashMap,
+        sync::{
+            Arc,
+            atomic::{AtomicU8, Ordering},
+        },
+    },
+};
+
+/// Configuration of the GCLOCK replacer.
+#[derive(Debug)]
+pub struct GclockConfig {
+    /// Maximum number of frames that can be stored in the replacer.
+    pub capacity: usize,
+
+    /// Maximum value of the reference counter.
+    ///
+    /// The higher it is, the longer frequently accessed frames survive
+    /// without being accessed. With the maximum of 1, GCLOCK works as Clock.
+    pub max_count: u8,
+}
+
+impl Default for GclockConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            max_count: 7,
+        }
+    }
+}
+
+/// State of a slot in the clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// No frame is stored in the slot.
+    Empty,
+
+    /// Frame is stored, but is not a candidate for eviction.
+    Pinned,
+
+    /// Frame is stored, and can be evicted.
+    Evictable,
+}
+
+/// Slot of the clock.
+#[derive(Debug)]
+struct Slot {
+    /// Reference counter, incremented on every access, and decremented every
+    /// time the hand passes the slot.
+    ///
+    /// The counter is atomic, so that it can be updated while holding a read
+    /// lock.
+    count: AtomicU8,
+
+    state: State,
+}
+
+/// Generalized Clock (GCLOCK) frame replacer.
+///
+/// Works as [`ClockReplacer`](crate::ClockReplacer), but instead of a
+/// reference bit every frame has a reference counter (see
+/// [`GclockConfig::max_count`]). The counter is incremented on every access,
+/// and when a victim is needed, the clock hand sweeps over the slots,
+/// decrementing the counters, until it finds an evictable frame whose counter
+/// is already zero. Frequently accessed frames thus survive several
+/// revolutions of the hand, while the ones accessed only once are evicted
+/// soon.
+///
+/// The hand does not actually sweep over the slots more than once: the slot
+/// it would stop at, and the amount every counter would be decremented by,
+/// are computed in a single pass.
+///
+/// Accessing a frame which is already stored only increments its counter, so
+/// it requires a read lock only.
+///
+/// Pinned frames stay in their slots, but are skipped by the hand (and keep
+/// their counters).
+pub struct GclockReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: GclockConfig,
+
+    slots: Vec<Slot>,
+
+    /// Position of the clock hand.
+    hand: usize,
+
+    /// Number of evictable frames.
+    size: usize,
+
+    /// Mapping of frame IDs to their slots.
+    ids: HashMap<F, usize>,
+
+    /// Frame IDs stored in slots.
+    frames: Vec<Option<F>>,
+
+    /// Empty slots.
+    free: Vec<usize>,
+}
+
+impl<F: FrameId> Default for GclockReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(GclockConfig::default())
+    }
+}
+
+impl<F: FrameId> GclockReplacer<F> {
+    /// Creates a new GCLOCK replacer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(GclockConfig {
+            capacity,
+            ..GclockConfig::default()
+        })
+    }
+
+    /// Creates a new GCLOCK replacer with the given configuration.
+    pub fn with_config(config: GclockConfig) -> Self {
+        let capacity = config.capacity;
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                config,
+                slots: (0..capacity)
+                    .map(|_| Slot {
+                        count: AtomicU8::new(0),
+                        state: State::Empty,
+                    })
+                    .collect(),
+                hand: 0,
+                size: 0,
+                ids: HashMap::with_capacity(capacity),
+                frames: vec![None; capacity],
+                // Slots are taken from the back, start with the lowest one.
+                free: (0..capacity).rev().collect(),
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    /// Increments the reference counter of the slot.
+    fn reference(&self, slot: usize) {
+        let max_count = self.config.max_count.max(1);
+        let _ =
+            self.slots[slot]
+                .count
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                    (count < max_count).then_some(count + 1)
+                });
+    }
+
+    fn set_state(&mut self, slot: usize, state: State) {
+        let s = &mut self.slots[slot];
+        match (s.state, state) {
+            (State::Evictable, State::Pinned | State::Empty) => self.size -= 1,
+            (State::Pinned | State::Empty, State::Evictable) => self.size += 1,
+            _ => {}
+        }
+        s.state = state;
+    }
+
+    /// Evictable slots, in the order the hand passes them, along with their
+    /// distances from the hand.
+    fn evictable(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let n = self.slots.len();
+        (0..n)
+            .map(move |distance| ((self.hand + distance) % n, distance))
+            .filter(|&(slot, _)| self.slots[slot].state == State::Evictable)
+    }
+
+    /// Finds the slot the hand stops at, without moving the hand.
+    ///
+    /// The hand reaches the slot with the counter `c` at the distance `d` for
+    /// the `c + 1`-th time after `c * n + d` steps (where `n` is the number of
+    /// slots), and stops at the slot where it happens first.
+    fn victim(&self) -> Option<(usize, usize, u8)> {
+        if self.size == 0 {
+            return None;
+        }
+        let n = self.slots.len();
+        self.evictable()
+            .map(|(slot, distance)| {
+                let count = self.slots[slot].count.load(Ordering::Relaxed);
+                (slot, distance, count)
+            })
+            .min_by_key(|&(_, distance, count)| count as usize * n + distance)
+    }
+
+    /// Moves the hand, decrementing the counters, until a victim is found.
+    ///
+    /// The victim's slot is emptied.
+    fn evict(&mut self) -> Option<usize> {
+        let (victim, victim_distance, victim_count) = self.victim()?;
+
+        // Every slot before the victim is passed once more than the ones after it.
+        let n = self.slots.len();
+        for distance in 0..n {
+            let s = &mut self.slots[(self.hand + distance) % n];
+            if s.state == State::Evictable {
+                let passes = victim_count.saturating_add(u8::from(distance < victim_distance));
+                let count = s.count.get_mut();
+                *count = count.saturating_sub(passes);
+            }
+        }
+
+        self.hand = (victim + 1) % n;
+        self.set_state(victim, State::Empty);
+        Some(victim)
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for GclockReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let slot = inner.evict()?;
+        let id = inner.frames[slot].take()?;
+        inner.ids.remove(&id);
+        inner.free.push(slot);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        let inner = self.inner.read();
+        inner
+            .victim()
+            .and_then(|(slot, ..)| inner.frames[slot].clone())
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        // Fast path: the frame is already stored, just bump its counter.
+        {
+            let inner = self.inner.read();
+            if let Some(&slot) = inner.ids.get(&id) {
+                inner.reference(slot);
+                return Ok(());
+            }
+        }
+
+        let mut inner = self.inner.write();
+        if let Some(&slot) = inner.ids.get(&id) {
+            inner.reference(slot);
+            return Ok(());
+        }
+
+        let slot = inner.free.pop().ok_or(EvictError::FrameReplacerFull)?;
+        *inner.slots[slot].count.get_mut() = 1;
+        inner.set_state(slot, State::Evictable);
+        inner.frames[slot] = Some(id.clone());
+        inner.ids.insert(id, slot);
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // GCLOCK does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let slot = *inner.ids.get(&id).ok_or(EvictError::InvalidFrameId(id))?;
+        inner.set_state(slot, State::Pinned);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(&slot) = inner.ids.get(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+        inner.set_state(slot, State::Evictable);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let Some(&slot) = inner.ids.get(&id) else {
+            return Ok(());
+        };
+        if inner.slots[slot].state == State::Pinned {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+        inner.set_state(slot, State::Empty);
+        inner.frames[slot] = None;
+        inner.ids.remove(&id);
+        inner.free.push(slot);
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().size
+    }
+}
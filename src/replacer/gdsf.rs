@@ -0,0 +1,246 @@
+//! GDSF page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the [GreedyDual-Size-Frequency](https://www.hpl.hp.com/techreports/98/HPL-98-173.pdf)
+//! policy.
+
+use {
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    priority_queue::PriorityQueue,
+    std::{cmp::Reverse, collections::HashMap, sync::Arc},
+};
+
+/// Eviction priority of a page.
+///
+/// Ties are broken by the sequence number of the last access, so that the
+/// least recently accessed page is evicted first.
+#[derive(Debug, Clone, Copy)]
+struct Priority {
+    value: f64,
+    seq: u64,
+}
+
+impl PartialEq for Priority {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Priority {}
+
+impl PartialOrd for Priority {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Priority {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.value
+            .total_cmp(&other.value)
+            .then_with(|| self.seq.cmp(&other.seq))
+    }
+}
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    /// Number of accesses to the page.
+    freq: u64,
+
+    /// Size of the page (in arbitrary units, e.g. bytes).
+    size: u64,
+
+    /// Cost of bringing the page back in, once it is evicted.
+    cost: f64,
+
+    /// Priority of the page, as of the last access.
+    priority: Priority,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+/// GreedyDual-Size-Frequency (GDSF) frame replacer.
+///
+/// Every frame has a size and a cost of a miss (e.g. time it takes to fetch or
+/// recompute the page), supplied with
+/// [`touch_with_cost`](Self::touch_with_cost). Frames touched in the regular
+/// way have both the size and the cost of 1 (or keep the ones supplied
+/// earlier), so, if no frame has its cost specified, the replacer works as LFU
+/// with dynamic aging.
+///
+/// On every access, the priority of the frame is set to
+/// `L + freq * cost / size`, where `freq` is the access count, and `L` is the
+/// inflation value. The frame with the lowest priority is evicted first, and
+/// `L` is raised to its priority. This way, frames which are cheap to bring
+/// back, or which occupy a lot of space, are evicted sooner, while frames
+/// which are not accessed anymore eventually fall behind the rising `L`.
+///
+/// Note that the capacity is still measured in frames: the replacer only
+/// decides the eviction order, it is up to the caller to evict frames until
+/// enough space is freed.
+///
+/// Pinned frames are taken out of the queue, but keep their access counts.
+pub struct GdsfReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Maximum number of frames that can be stored in the replacer.
+    capacity: usize,
+
+    /// Inflation value, the priority of the last evicted frame.
+    inflation: f64,
+
+    /// Sequence number of the last access.
+    seq: u64,
+
+    /// Mapping of frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    /// Evictable frames, ordered by their priorities.
+    queue: PriorityQueue<F, Reverse<Priority>>,
+}
+
+impl<F: FrameId> GdsfReplacer<F> {
+    /// Creates a new GDSF replacer.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                capacity,
+                inflation: 0.0,
+                seq: 0,
+                pages: HashMap::with_capacity(capacity),
+                queue: PriorityQueue::with_capacity(capacity),
+            })),
+        }
+    }
+
+    /// Notifies the replacer that the frame has been accessed, updating its
+    /// size and cost of a miss.
+    ///
+    /// The size of zero is treated as one.
+    pub fn touch_with_cost(&self, id: F, size: u64, cost: f64) -> EvictResult<(), F> {
+        self.access(id, Some((size.max(1), cost)))
+    }
+
+    /// Registers access to the frame, optionally updating its size and cost.
+    fn access(&self, id: F, metadata: Option<(u64, f64)>) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // The replacer is full, cannot add new page.
+        if inner.pages.len() >= inner.capacity && !inner.pages.contains_key(&id) {
+            return Err(EvictError::FrameReplacerFull);
+        }
+
+        inner.seq += 1;
+        let (seq, inflation) = (inner.seq, inner.inflation);
+
+        let page = inner.pages.entry(id.clone()).or_insert(PageInfo {
+            freq: 0,
+            size: 1,
+            cost: 1.0,
+            priority: Priority { value: 0.0, seq },
+            evictable: true,
+        });
+        if let Some((size, cost)) = metadata {
+            page.size = size;
+            page.cost = cost;
+        }
+        page.freq += 1;
+        page.priority = Priority {
+            value: inflation + page.freq as f64 * page.cost / page.size as f64,
+            seq,
+        };
+
+        if page.evictable {
+            let priority = page.priority;
+            inner.queue.push(id, Reverse(priority));
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for GdsfReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let (id, Reverse(priority)) = inner.queue.pop()?;
+        inner.pages.remove(&id);
+
+        // Frames with priority below the current inflation value (e.g. the ones
+        // that have been pinned for a while) do not deflate it.
+        inner.inflation = inner.inflation.max(priority.value);
+
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        self.inner.read().queue.peek().map(|(id, _)| id.clone())
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        self.access(id, None)
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // GDSF does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let page = inner
+            .pages
+            .get_mut(&id)
+            .ok_or(EvictError::InvalidFrameId(id.clone()))?;
+        page.evictable = false;
+        inner.queue.remove(&id);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(page) = inner.pages.get_mut(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+
+        // No-op if the frame is already in the desired state.
+        if std::mem::replace(&mut page.evictable, true) {
+            return Ok(());
+        }
+        let priority = page.priority;
+        inner.queue.push(id, Reverse(priority));
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if let Some(page) = inner.pages.get(&id) {
+            if !page.evictable {
+                return Err(EvictError::PinnedFrameRemoval(id));
+            }
+            inner.pages.remove(&id);
+            inner.queue.remove(&id);
+        }
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().queue.len()
+    }
+}
@@ -0,0 +1,284 @@
+//! Hyperbolic page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the [Hyperbolic Caching paper](https://www.usenix.org/conference/atc17/technical-sessions/presentation/blankstein).
+
+use {
+    super::{rng::Rng, sample::SampleSet},
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{
+        collections::HashMap,
+        sync::{
+            Arc,
+            atomic::{AtomicU64, Ordering},
+        },
+    },
+};
+
+/// Configuration of the Hyperbolic replacer.
+#[derive(Debug)]
+pub struct HyperbolicConfig {
+    /// Maximum number of frames that can be stored in the replacer.
+    pub capacity: usize,
+
+    /// Number of frames sampled on eviction, the one with the lowest priority
+    /// among them is evicted.
+    ///
+    /// The more frames are sampled, the closer the victim is to the one with
+    /// the lowest priority overall, at the expense of slower eviction.
+    pub samples: usize,
+
+    /// Seed of the pseudo-random generator, used to sample the frames.
+    pub seed: u64,
+}
+
+impl Default for HyperbolicConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            samples: 64,
+            seed: 0,
+        }
+    }
+}
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    /// Number of accesses to the page since it was inserted.
+    ///
+    /// The counter is atomic, so that it can be updated while holding a read
+    /// lock.
+    hits: AtomicU64,
+
+    /// Time the page was inserted at.
+    inserted_at: u64,
+
+    /// Cost of bringing the page back in, once it is evicted.
+    cost: f64,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+impl PageInfo {
+    /// Priority of the page at the given time.
+    fn priority(&self, now: u64) -> f64 {
+        let age = now.saturating_sub(self.inserted_at).max(1) as f64;
+        self.cost * self.hits.load(Ordering::Relaxed) as f64 / age
+    }
+}
+
+/// Hyperbolic frame replacer.
+///
+/// The priority of a frame is the number of accesses to it, divided by the
+/// time it has spent in the replacer, and multiplied by its cost (see
+/// [`touch_with_cost`](Self::touch_with_cost)). Time is measured in
+/// accesses (to any frame), so the priority of every frame decays over time,
+/// unless the frame keeps being accessed. Frames touched in the regular way
+/// have the cost of 1 (or keep the one supplied earlier).
+///
+/// Since priorities change all the time, they are not kept in any order.
+/// Instead, a few frames are sampled at random on eviction (see
+/// [`HyperbolicConfig::samples`]), and the one with the lowest priority among
+/// them is evicted. Accessing a frame which is already stored only bumps its
+/// hit counter, so it requires a read lock only.
+///
+/// The pseudo-random generator is seeded explicitly (see
+/// [`HyperbolicConfig::seed`]), so that the same sequence of operations always
+/// results in the same sequence of victims.
+///
+/// Pinned frames are excluded from sampling, but keep their hit counters.
+pub struct HyperbolicReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: HyperbolicConfig,
+
+    /// Current time, incremented on every access.
+    ///
+    /// The counter is atomic, so that it can be updated while holding a read
+    /// lock.
+    time: AtomicU64,
+
+    /// Mapping of frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    /// Evictable frames.
+    frames: SampleSet<F>,
+
+    rng: Rng,
+}
+
+impl<F: FrameId> Default for HyperbolicReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(HyperbolicConfig::default())
+    }
+}
+
+impl<F: FrameId> HyperbolicReplacer<F> {
+    /// Creates a new Hyperbolic replacer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(HyperbolicConfig {
+            capacity,
+            ..HyperbolicConfig::default()
+        })
+    }
+
+    /// Creates a new Hyperbolic replacer with the given configuration.
+    pub fn with_config(config: HyperbolicConfig) -> Self {
+        let capacity = config.capacity;
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                time: AtomicU64::new(0),
+                pages: HashMap::with_capacity(capacity),
+                frames: SampleSet::with_capacity(capacity),
+                rng: Rng::new(config.seed),
+                config,
+            })),
+        }
+    }
+
+    /// Notifies the replacer that the frame has been accessed, updating its
+    /// cost of a miss.
+    pub fn touch_with_cost(&self, id: F, cost: f64) -> EvictResult<(), F> {
+        self.access(id, Some(cost))
+    }
+
+    /// Registers access to the frame, optionally updating its cost.
+    fn access(&self, id: F, cost: Option<f64>) -> EvictResult<(), F> {
+        // Fast path: the frame is already stored, just bump its hit counter.
+        if cost.is_none() {
+            let inner = self.inner.read();
+            if let Some(page) = inner.pages.get(&id) {
+                inner.time.fetch_add(1, Ordering::Relaxed);
+                page.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(());
+            }
+        }
+
+        let mut inner = self.inner.write();
+        if !inner.pages.contains_key(&id) && inner.pages.len() >= inner.config.capacity {
+            return Err(EvictError::FrameReplacerFull);
+        }
+        let now = *inner.time.get_mut() + 1;
+        *inner.time.get_mut() = now;
+
+        match inner.pages.get_mut(&id) {
+            Some(page) => {
+                *page.hits.get_mut() += 1;
+                if let Some(cost) = cost {
+                    page.cost = cost;
+                }
+            }
+            None => {
+                inner.pages.insert(id.clone(), PageInfo {
+                    hits: AtomicU64::new(1),
+                    inserted_at: now,
+                    cost: cost.unwrap_or(1.0),
+                    evictable: true,
+                });
+                inner.frames.insert(id);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    /// Frame with the lowest priority among the sampled ones.
+    fn victim(&self, rng: &mut Rng) -> Option<F> {
+        let now = self.time.load(Ordering::Relaxed);
+        let mut victim: Option<(&F, f64)> = None;
+        for id in self.frames.sample(rng, self.config.samples.max(1)) {
+            let Some(page) = self.pages.get(id) else {
+                continue;
+            };
+            let priority = page.priority(now);
+            if victim.is_none_or(|(_, lowest)| priority < lowest) {
+                victim = Some((id, priority));
+            }
+        }
+        victim.map(|(id, _)| id.clone())
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for HyperbolicReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let mut rng = inner.rng.clone();
+        let id = inner.victim(&mut rng)?;
+        inner.rng = rng;
+        inner.frames.remove(&id);
+        inner.pages.remove(&id);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        let inner = self.inner.read();
+        // Use a copy of the generator, so that the next eviction picks the same frame.
+        inner.victim(&mut inner.rng.clone())
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        self.access(id, None)
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // Hyperbolic does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let page = inner
+            .pages
+            .get_mut(&id)
+            .ok_or(EvictError::InvalidFrameId(id.clone()))?;
+        page.evictable = false;
+        inner.frames.remove(&id);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(page) = inner.pages.get_mut(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+        page.evictable = true;
+        inner.frames.insert(id);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if let Some(page) = inner.pages.get(&id) {
+            if !page.evictable {
+                return Err(EvictError::PinnedFrameRemoval(id));
+            }
+            inner.pages.remove(&id);
+            inner.frames.remove(&id);
+        }
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().frames.len()
+    }
+}
@@ -0,0 +1,457 @@
+//! LeCaR and CACHEUS page replacement algorithms.
+//!
+//! The algorithms implemented here are based on the [LeCaR paper](https://www.usenix.org/conference/hotstorage18/presentation/vietri)
+//! and the [CACHEUS paper](https://www.usenix.org/conference/fast21/presentation/rodriguez).
+
+use {
+    super::{list::FrameList, rng::Rng},
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    priority_queue::PriorityQueue,
+    std::{cmp::Reverse, collections::HashMap, sync::Arc},
+};
+
+/// Number of consecutive windows without improvement of the hit rate, after
+/// which CACHEUS restarts the learning rate at random.
+const UNLEARN_LIMIT: usize = 10;
+
+/// Minimum learning rate of CACHEUS.
+const MIN_LEARNING_RATE: f64 = 0.001;
+
+/// Configuration of the LeCaR replacer.
+#[derive(Debug)]
+pub struct LecarConfig {
+    /// Maximum number of resident frames. Each of the ghost histories holds
+    /// up to that many frame identifiers as well.
+    pub capacity: usize,
+
+    /// Learning rate (`λ` in the LeCaR paper): the larger it is, the faster the
+    /// weights of the experts react to their mistakes.
+    ///
+    /// With adaptive learning rate, this is the initial value.
+    pub learning_rate: f64,
+
+    /// Discount of the regret: a mistake discovered `t` accesses after the
+    /// eviction weighs `discount^(t / capacity)`, so that the older mistakes
+    /// matter less.
+    pub discount: f64,
+
+    /// Whether to adapt the learning rate (CACHEUS).
+    ///
+    /// With adaptive learning rate, the hit rate is measured over windows of
+    /// `capacity` accesses, and the learning rate is moved in the direction
+    /// that improved the hit rate. If the hit rate does not improve for a
+    /// while, the learning rate is restarted at random.
+    pub adaptive_learning_rate: bool,
+
+    /// Seed of the pseudo-random generator, used to pick the expert.
+    pub seed: u64,
+}
+
+impl Default for LecarConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            learning_rate: 0.45,
+            discount: 0.005,
+            adaptive_learning_rate: false,
+            seed: 0,
+        }
+    }
+}
+
+/// Expert, i.e. one of the policies the replacer learns to mix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expert {
+    Lru,
+    Lfu,
+}
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    /// Number of accesses to the page, including the ones made before it was
+    /// evicted last time (if it is still remembered in a history).
+    freq: u64,
+
+    /// Time of the last access.
+    last_ref: u64,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+/// Evicted page, remembered in the history of the expert which evicted it.
+#[derive(Debug)]
+struct Ghost {
+    expert: Expert,
+
+    /// Access count of the page at the time of eviction.
+    freq: u64,
+
+    /// Time of eviction.
+    evicted_at: u64,
+}
+
+/// State of the adaptive learning rate.
+#[derive(Debug, Default)]
+struct Adaptation {
+    /// Learning rate during the previous window.
+    prev_learning_rate: f64,
+
+    /// Hit rate during the previous window.
+    prev_hit_rate: f64,
+
+    /// Number of hits during the current window.
+    hits: usize,
+
+    /// Number of accesses during the current window.
+    accesses: usize,
+
+    /// Number of consecutive windows without improvement of the hit rate.
+    unlearn: usize,
+}
+
+/// LeCaR (Learning Cache Replacement) frame replacer.
+///
+/// Mixes two experts, LRU and LFU, each with a weight and a ghost history of
+/// the frames it has evicted. On eviction, one of the experts is picked at
+/// random, with probability equal to its weight, and its victim is evicted
+/// and remembered in its history (unless both experts agree on the victim).
+///
+/// When a frame missing from the replacer is found in the history of an
+/// expert, that expert is considered to have made a mistake, so the weight of
+/// the other expert is increased (regret minimization, see
+/// [`LecarConfig::learning_rate`] and [`LecarConfig::discount`]). Frames
+/// coming back from a history restore their access counts.
+///
+/// In CACHEUS mode (see [`LecarConfig::adaptive_learning_rate`]), the learning
+/// rate itself is adapted, so that it does not have to be tuned for the
+/// workload.
+///
+/// The pseudo-random generator is seeded explicitly (see
+/// [`LecarConfig::seed`]), so that the same sequence of operations always
+/// results in the same sequence of victims.
+///
+/// Pinned frames keep their positions and access counts, but are skipped by
+/// both experts.
+pub struct LecarReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: LecarConfig,
+
+    /// Current time, incremented on every access.
+    time: u64,
+
+    /// Mapping of resident frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    /// Resident frames in LRU order, the least recently used frame is at the
+    /// front.
+    lru: FrameList<F>,
+
+    /// Evictable frames, ordered by their access counts (and then recency).
+    lfu: PriorityQueue<F, Reverse<(u64, u64)>>,
+
+    /// Ghost history of the LRU expert, the least recently evicted frame is at
+    /// the front.
+    lru_history: FrameList<F>,
+
+    /// Ghost history of the LFU expert, the least recently evicted frame is at
+    /// the front.
+    lfu_history: FrameList<F>,
+
+    /// Mapping of evicted frame IDs to their ghost entries.
+    ghosts: HashMap<F, Ghost>,
+
+    /// Weight of the LRU expert, the weight of the LFU expert is `1 - w`.
+    lru_weight: f64,
+
+    /// Current learning rate.
+    learning_rate: f64,
+
+    /// State of the adaptive learning rate, with CACHEUS only.
+    adaptation: Option<Adaptation>,
+
+    rng: Rng,
+}
+
+impl<F: FrameId> Default for LecarReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(LecarConfig::default())
+    }
+}
+
+impl<F: FrameId> LecarReplacer<F> {
+    /// Creates a new LeCaR replacer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(LecarConfig {
+            capacity,
+            ..LecarConfig::default()
+        })
+    }
+
+    /// Creates a new CACHEUS replacer (LeCaR with adaptive learning rate) with
+    /// the given capacity.
+    pub fn with_adaptive_learning_rate(capacity: usize) -> Self {
+        Self::with_config(LecarConfig {
+            capacity,
+            adaptive_learning_rate: true,
+            ..LecarConfig::default()
+        })
+    }
+
+    /// Creates a new LeCaR replacer with the given configuration.
+    pub fn with_config(config: LecarConfig) -> Self {
+        let capacity = config.capacity;
+        let adaptation = config.adaptive_learning_rate.then(Adaptation::default);
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                time: 0,
+                pages: HashMap::with_capacity(capacity),
+                lru: FrameList::with_capacity(capacity),
+                lfu: PriorityQueue::with_capacity(capacity),
+                lru_history: FrameList::with_capacity(capacity),
+                lfu_history: FrameList::with_capacity(capacity),
+                ghosts: HashMap::with_capacity(2 * capacity),
+                lru_weight: 0.5,
+                learning_rate: config.learning_rate,
+                adaptation,
+                rng: Rng::new(config.seed),
+                config,
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    fn history(&mut self, expert: Expert) -> &mut FrameList<F> {
+        match expert {
+            Expert::Lru => &mut self.lru_history,
+            Expert::Lfu => &mut self.lfu_history,
+        }
+    }
+
+    /// Victim, and the expert to blame for it (`None` if both experts agree).
+    ///
+    /// The expert is picked with the given generator.
+    fn victim(&self, rng: &mut Rng) -> Option<(F, Option<Expert>)> {
+        let (lfu, _) = self.lfu.peek()?;
+        let lru = self
+            .lru
+            .iter()
+            .find(|id| self.pages.get(*id).is_some_and(|page| page.evictable))?;
+        if lru == lfu {
+            return Some((lru.clone(), None));
+        }
+        if rng.next_f64() < self.lru_weight {
+            Some((lru.clone(), Some(Expert::Lru)))
+        } else {
+            Some((lfu.clone(), Some(Expert::Lfu)))
+        }
+    }
+
+    /// Penalizes the expert which evicted the page that is accessed again.
+    fn regret(&mut self, ghost: &Ghost) {
+        let elapsed = (self.time - ghost.evicted_at) as f64;
+        let capacity = self.config.capacity.max(1) as f64;
+        let regret = self.config.discount.powf(elapsed / capacity);
+        let reward = (self.learning_rate * regret).exp();
+
+        let (mut lru, mut lfu) = (self.lru_weight, 1.0 - self.lru_weight);
+        match ghost.expert {
+            Expert::Lru => lfu *= reward,
+            Expert::Lfu => lru *= reward,
+        }
+        self.lru_weight = lru / (lru + lfu);
+    }
+
+    /// Records the access in the current window, and adapts the learning rate
+    /// at the end of the window.
+    fn adapt(&mut self, hit: bool) {
+        let window = self.config.capacity.max(1);
+        let Some(adaptation) = self.adaptation.as_mut() else {
+            return;
+        };
+        adaptation.accesses += 1;
+        if hit {
+            adaptation.hits += 1;
+        }
+        if adaptation.accesses < window {
+            return;
+        }
+
+        let hit_rate = adaptation.hits as f64 / adaptation.accesses as f64;
+        let delta_hit_rate = hit_rate - adaptation.prev_hit_rate;
+        let delta_learning_rate = self.learning_rate - adaptation.prev_learning_rate;
+        adaptation.prev_hit_rate = hit_rate;
+        adaptation.prev_learning_rate = self.learning_rate;
+        adaptation.hits = 0;
+        adaptation.accesses = 0;
+
+        if delta_learning_rate != 0.0 && delta_hit_rate != 0.0 {
+            // Keep moving in the direction which improved the hit rate, turn
+            // around otherwise.
+            let step = (self.learning_rate * delta_learning_rate).abs();
+            let step = if (delta_hit_rate > 0.0) == (delta_learning_rate > 0.0) {
+                step
+            } else {
+                -step
+            };
+            self.learning_rate = (self.learning_rate + step).clamp(MIN_LEARNING_RATE, 1.0);
+            adaptation.unlearn = 0;
+        } else if hit_rate == 0.0 || delta_hit_rate <= 0.0 {
+            adaptation.unlearn += 1;
+            if adaptation.unlearn >= UNLEARN_LIMIT {
+                adaptation.unlearn = 0;
+                self.learning_rate =
+                    MIN_LEARNING_RATE + self.rng.next_f64() * (1.0 - MIN_LEARNING_RATE);
+            }
+        }
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for LecarReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let mut rng = inner.rng.clone();
+        let (id, expert) = inner.victim(&mut rng)?;
+        inner.rng = rng;
+
+        let page = inner.pages.remove(&id)?;
+        inner.lru.remove(&id);
+        inner.lfu.remove(&id);
+
+        // Remember the victim in the history of the expert which picked it.
+        if let Some(expert) = expert {
+            let limit = inner.config.capacity;
+            let history = inner.history(expert);
+            let forgotten = (history.len() >= limit)
+                .then(|| history.pop_front())
+                .flatten();
+            history.push_back(id.clone());
+            if let Some(forgotten) = forgotten {
+                inner.ghosts.remove(&forgotten);
+            }
+            let evicted_at = inner.time;
+            inner.ghosts.insert(id.clone(), Ghost {
+                expert,
+                freq: page.freq,
+                evicted_at,
+            });
+        }
+
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        let inner = self.inner.read();
+        // Use a copy of the generator, so that the next eviction picks the same frame.
+        inner.victim(&mut inner.rng.clone()).map(|(id, _)| id)
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let hit = inner.pages.contains_key(&id);
+        if !hit && inner.pages.len() >= inner.config.capacity {
+            return Err(EvictError::FrameReplacerFull);
+        }
+        inner.time += 1;
+        inner.adapt(hit);
+
+        let freq = match inner.pages.get(&id) {
+            Some(page) => page.freq,
+            None => match inner.ghosts.remove(&id) {
+                // Missed frame is found in a history, so the expert which evicted
+                // it has made a mistake.
+                Some(ghost) => {
+                    inner.history(ghost.expert).remove(&id);
+                    inner.regret(&ghost);
+                    ghost.freq
+                }
+                None => 0,
+            },
+        } + 1;
+
+        let time = inner.time;
+        let page = inner.pages.entry(id.clone()).or_insert(PageInfo {
+            freq,
+            last_ref: time,
+            evictable: true,
+        });
+        page.freq = freq;
+        page.last_ref = time;
+        if page.evictable {
+            inner.lfu.push(id.clone(), Reverse((freq, time)));
+        }
+        inner.lru.push_back(id);
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // LeCaR does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let page = inner
+            .pages
+            .get_mut(&id)
+            .ok_or(EvictError::InvalidFrameId(id.clone()))?;
+        page.evictable = false;
+        inner.lfu.remove(&id);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(page) = inner.pages.get_mut(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+
+        // No-op if the frame is already in the desired state.
+        if std::mem::replace(&mut page.evictable, true) {
+            return Ok(());
+        }
+        let priority = Reverse((page.freq, page.last_ref));
+        inner.lfu.push(id, priority);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if let Some(page) = inner.pages.get(&id) {
+            if !page.evictable {
+                return Err(EvictError::PinnedFrameRemoval(id));
+            }
+            // Removed frames are not remembered in the histories.
+            inner.pages.remove(&id);
+            inner.lru.remove(&id);
+            inner.lfu.remove(&id);
+        }
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().lfu.len()
+    }
+}
@@ -0,0 +1,340 @@
+//! LFU page replacement algorithm.
+//!
+//! The constant time implementation is based on the [O(1) LFU paper](http://dhruvbird.com/lfu.pdf),
+//! while the dynamic aging follows the [LFUDA](https://www.hpl.hp.com/techreports/98/HPL-98-173.pdf)
+//! policy.
+
+use {
+    super::list::FrameList,
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{collections::HashMap, sync::Arc},
+};
+
+/// Configuration of the LFU replacer.
+#[derive(Debug)]
+pub struct LfuConfig {
+    /// Maximum number of frames to keep track of.
+    pub capacity: usize,
+
+    /// Whether to use dynamic aging (LFUDA).
+    ///
+    /// With dynamic aging, the priority of a frame is its access count plus
+    /// the cache age, which is set to the priority of the last evicted frame.
+    /// This way, frames which were hot long ago, but are not accessed anymore,
+    /// are eventually evicted.
+    pub dynamic_aging: bool,
+}
+
+impl Default for LfuConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            dynamic_aging: false,
+        }
+    }
+}
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    /// Number of accesses to the page.
+    freq: u64,
+
+    /// Priority of the page, i.e. the key of the bucket it resides in.
+    priority: u64,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+/// Frames with the same priority.
+#[derive(Debug)]
+struct Bucket<F: FrameId> {
+    /// Frames in the order of their arrival into the bucket, the least recently
+    /// accessed frame is at the front.
+    frames: FrameList<F>,
+
+    /// Key of the bucket with the next lower priority.
+    prev: Option<u64>,
+
+    /// Key of the bucket with the next higher priority.
+    next: Option<u64>,
+}
+
+/// Least Frequently Used (LFU) frame replacer.
+///
+/// Frames are grouped into buckets by their priority (access count), and the
+/// buckets are kept in a linked list ordered by priority. Since every access
+/// increases the priority by one, a frame only ever moves into the adjacent
+/// bucket, so `touch`, `evict` and `peek` are all O(1). Ties within a bucket
+/// are broken by recency: the least recently accessed frame is evicted first.
+///
+/// In dynamic aging mode (see [`LfuConfig::dynamic_aging`]), a priority may
+/// skip over a few buckets, when the cache age has grown since the frame's
+/// previous access.
+///
+/// Pinned frames stay in their buckets (so they keep their access counts), but
+/// are never picked as victims.
+pub struct LfuReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: LfuConfig,
+
+    /// Cache age (`L` in the LFUDA paper), always zero without dynamic aging.
+    age: u64,
+
+    /// Number of evictable frames in the replacer.
+    size: usize,
+
+    /// Mapping of frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    /// Buckets by their priority.
+    buckets: HashMap<u64, Bucket<F>>,
+
+    /// Key of the bucket with the lowest priority.
+    head: Option<u64>,
+}
+
+impl<F: FrameId> Default for LfuReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(LfuConfig::default())
+    }
+}
+
+impl<F: FrameId> LfuReplacer<F> {
+    /// Creates a new LFU replacer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(LfuConfig {
+            capacity,
+            ..LfuConfig::default()
+        })
+    }
+
+    /// Creates a new LFU replacer with the given configuration.
+    pub fn with_config(config: LfuConfig) -> Self {
+        let capacity = config.capacity;
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                config,
+                age: 0,
+                size: 0,
+                pages: HashMap::with_capacity(capacity),
+                buckets: HashMap::new(),
+                head: None,
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    /// Priority of a frame with the given access count.
+    fn priority(&self, freq: u64) -> u64 {
+        if self.config.dynamic_aging {
+            self.age + freq
+        } else {
+            freq
+        }
+    }
+
+    /// Puts the frame into the bucket with the given priority.
+    ///
+    /// The search for the bucket starts right after the `hint` bucket (or from
+    /// the head, if there is no hint), which must have lower priority.
+    fn insert(&mut self, id: F, priority: u64, hint: Option<u64>) {
+        let mut prev = hint;
+        let next = loop {
+            let next = match prev {
+                Some(key) => self.buckets.get(&key).and_then(|bucket| bucket.next),
+                None => self.head,
+            };
+            match next {
+                Some(key) if key < priority => prev = Some(key),
+                _ => break next,
+            }
+        };
+
+        if next != Some(priority) {
+            // Link a new bucket in-between `prev` and `next`.
+            match prev.and_then(|key| self.buckets.get_mut(&key)) {
+                Some(bucket) => bucket.next = Some(priority),
+                None => self.head = Some(priority),
+            }
+            if let Some(bucket) = next.and_then(|key| self.buckets.get_mut(&key)) {
+                bucket.prev = Some(priority);
+            }
+            self.buckets.insert(priority, Bucket {
+                frames: FrameList::default(),
+                prev,
+                next,
+            });
+        }
+        if let Some(bucket) = self.buckets.get_mut(&priority) {
+            bucket.frames.push_back(id);
+        }
+    }
+
+    /// Takes the frame out of its bucket, removing the bucket if it becomes
+    /// empty.
+    ///
+    /// Returns the key of the closest bucket with the same or lower priority.
+    fn detach(&mut self, id: &F, priority: u64) -> Option<u64> {
+        let bucket = self.buckets.get_mut(&priority)?;
+        bucket.frames.remove(id);
+        if !bucket.frames.is_empty() {
+            return Some(priority);
+        }
+
+        let Bucket { prev, next, .. } = self.buckets.remove(&priority)?;
+        match prev {
+            Some(key) => self.buckets.get_mut(&key)?.next = next,
+            None => self.head = next,
+        }
+        if let Some(key) = next {
+            self.buckets.get_mut(&key)?.prev = prev;
+        }
+        prev
+    }
+
+    /// Finds the next victim, along with its priority.
+    fn victim(&self) -> Option<(F, u64)> {
+        let mut key = self.head;
+        while let Some(priority) = key {
+            let bucket = self.buckets.get(&priority)?;
+            let victim = bucket
+                .frames
+                .iter()
+                .find(|id| self.pages.get(*id).is_some_and(|page| page.evictable));
+            if let Some(id) = victim {
+                return Some((id.clone(), priority));
+            }
+            key = bucket.next;
+        }
+        None
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for LfuReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let (id, priority) = inner.victim()?;
+
+        inner.detach(&id, priority);
+        inner.pages.remove(&id);
+        inner.size -= 1;
+        if inner.config.dynamic_aging {
+            inner.age = priority;
+        }
+
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        self.inner.read().victim().map(|(id, _)| id)
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let Some(page) = inner.pages.get_mut(&id) else {
+            // The replacer is full, cannot add new page.
+            if inner.pages.len() >= inner.config.capacity {
+                return Err(EvictError::FrameReplacerFull);
+            }
+            let priority = inner.priority(1);
+            inner.pages.insert(id.clone(), PageInfo {
+                freq: 1,
+                priority,
+                evictable: true,
+            });
+            inner.size += 1;
+            inner.insert(id, priority, None);
+            return Ok(());
+        };
+
+        page.freq += 1;
+        let (freq, old_priority) = (page.freq, page.priority);
+        let priority = inner.priority(freq);
+        if let Some(page) = inner.pages.get_mut(&id) {
+            page.priority = priority;
+        }
+
+        let hint = inner.detach(&id, old_priority);
+        inner.insert(id, priority, hint);
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // LFU does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let page = inner
+            .pages
+            .get_mut(&id)
+            .ok_or(EvictError::InvalidFrameId(id))?;
+
+        // No-op if the frame is already in the desired state.
+        if !page.evictable {
+            return Ok(());
+        }
+
+        page.evictable = false;
+        inner.size -= 1;
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(page) = inner.pages.get_mut(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+
+        // No-op if the frame is already in the desired state.
+        if page.evictable {
+            return Ok(());
+        }
+
+        page.evictable = true;
+        inner.size += 1;
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if let Some(page) = inner.pages.get(&id) {
+            if !page.evictable {
+                return Err(EvictError::PinnedFrameRemoval(id));
+            }
+            let priority = page.priority;
+            inner.detach(&id, priority);
+            inner.pages.remove(&id);
+            inner.size -= 1;
+        }
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().size
+    }
+}
@@ -0,0 +1,366 @@
+//! LHD page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the [LHD paper](https://www.usenix.org/conference/nsdi18/presentation/beckmann).
+
+use {
+    super::{rng::Rng, sample::SampleSet},
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{collections::HashMap, sync::Arc},
+};
+
+/// Number of frame classes, frames are classified by the number of hits
+/// (`0`, `1`, `2..4`, `4..8` etc).
+const CLASSES: usize = 16;
+
+/// Number of distinct (coarsened) ages, older frames share the last one.
+const AGES: usize = 256;
+
+/// Weight of the statistics gathered before the last reconfiguration.
+const EWMA_DECAY: f64 = 0.9;
+
+/// Minimum number of accesses between reconfigurations.
+const MIN_RECONFIGURATION_INTERVAL: u64 = 1024;
+
+/// Configuration of the LHD replacer.
+#[derive(Debug)]
+pub struct LhdConfig {
+    /// Maximum number of frames that can be stored in the replacer.
+    pub capacity: usize,
+
+    /// Number of frames sampled on eviction, the one with the lowest hit
+    /// density among them is evicted.
+    pub samples: usize,
+
+    /// Seed of the pseudo-random generator, used to sample the frames.
+    pub seed: u64,
+}
+
+impl Default for LhdConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            samples: 32,
+            seed: 0,
+        }
+    }
+}
+
+/// Statistics of a frame class.
+#[derive(Debug)]
+struct Class {
+    /// Number of hits at each age.
+    hits: Vec<f64>,
+
+    /// Number of evictions at each age.
+    evictions: Vec<f64>,
+
+    /// Hit density at each age, as of the last reconfiguration.
+    densities: Vec<f64>,
+}
+
+impl Class {
+    fn new(class: usize) -> Self {
+        Self {
+            hits: vec![0.0; AGES],
+            evictions: vec![0.0; AGES],
+            // Before anything is learned, favour younger frames with more hits.
+            densities: (0..AGES)
+                .map(|age| (class + 1) as f64 / (age + 1) as f64)
+                .collect(),
+        }
+    }
+
+    /// Recomputes hit densities from the gathered statistics, and decays the
+    /// statistics.
+    ///
+    /// The hit density at the given age is the number of hits frames of that
+    /// age (or older) are expected to get, divided by the expected time they
+    /// are going to spend in the replacer until they are hit or evicted.
+    fn reconfigure(&mut self) {
+        let (mut hits, mut events, mut lifetime) = (0.0, 0.0, 0.0);
+        for age in (0..AGES).rev() {
+            hits += self.hits[age];
+            events += self.hits[age] + self.evictions[age];
+            lifetime += events;
+            self.densities[age] = if lifetime > 0.0 { hits / lifetime } else { 0.0 };
+        }
+        for count in self.hits.iter_mut().chain(self.evictions.iter_mut()) {
+            *count *= EWMA_DECAY;
+        }
+    }
+}
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    /// Time of the last access.
+    last_access: u64,
+
+    /// Number of hits since the page was inserted.
+    hits: u64,
+
+    /// Size of the page (in arbitrary units, e.g. bytes).
+    size: u64,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+impl PageInfo {
+    fn class(&self) -> usize {
+        ((u64::BITS - self.hits.leading_zeros()) as usize).min(CLASSES - 1)
+    }
+}
+
+/// Least Hit Density (LHD) frame replacer.
+///
+/// Frames are ranked by their hit density: the number of hits a frame is
+/// expected to get, per unit of space-time it is expected to occupy (its size
+/// multiplied by the time it is going to spend in the replacer). Frames are
+/// classified by the number of hits they got so far, and for every class the
+/// replacer learns at which ages frames get hit or evicted. The hit density of
+/// a frame is then derived from the distributions of its class, conditioned on
+/// its age, and divided by its size.
+///
+/// Time is measured in accesses (to any frame), and ages are coarsened, so
+/// that ages up to eight times the capacity are distinguished. The
+/// distributions are gathered continuously, and the hit densities are
+/// recomputed periodically (every `capacity` accesses, but no more often than
+/// every 1024 accesses), with the older statistics decayed. Until the first
+/// recomputation, younger frames, and frames with more hits, are favoured, so
+/// the replacer works roughly as LRU.
+///
+/// Every frame has a size, supplied with
+/// [`touch_with_size`](Self::touch_with_size). Frames touched in the regular
+/// way have the size of 1 (or keep the one supplied earlier). Note that the
+/// capacity is still measured in frames: the replacer only decides the
+/// eviction order, it is up to the caller to evict frames until enough space
+/// is freed.
+///
+/// Since hit densities change with age, they are not kept in any order.
+/// Instead, a few frames are sampled at random on eviction (see
+/// [`LhdConfig::samples`]), and the one with the lowest hit density among
+/// them is evicted. The pseudo-random generator is seeded explicitly (see
+/// [`LhdConfig::seed`]), so that the same sequence of operations always
+/// results in the same sequence of victims.
+///
+/// Pinned frames are excluded from sampling, but keep their statistics.
+pub struct LhdReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: LhdConfig,
+
+    /// Current time, incremented on every access.
+    time: u64,
+
+    /// Number of time units per age.
+    coarsening: u64,
+
+    /// Number of accesses between reconfigurations.
+    reconfiguration_interval: u64,
+
+    /// Statistics of the frame classes.
+    classes: Vec<Class>,
+
+    /// Mapping of frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    /// Evictable frames.
+    frames: SampleSet<F>,
+
+    rng: Rng,
+}
+
+impl<F: FrameId> Default for LhdReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(LhdConfig::default())
+    }
+}
+
+impl<F: FrameId> LhdReplacer<F> {
+    /// Creates a new LHD replacer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(LhdConfig {
+            capacity,
+            ..LhdConfig::default()
+        })
+    }
+
+    /// Creates a new LHD replacer with the given configuration.
+    pub fn with_config(config: LhdConfig) -> Self {
+        let capacity = config.capacity;
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                time: 0,
+                coarsening: (capacity as u64 * 8 / AGES as u64).max(1),
+                reconfiguration_interval: (capacity as u64).max(MIN_RECONFIGURATION_INTERVAL),
+                classes: (0..CLASSES).map(Class::new).collect(),
+                pages: HashMap::with_capacity(capacity),
+                frames: SampleSet::with_capacity(capacity),
+                rng: Rng::new(config.seed),
+                config,
+            })),
+        }
+    }
+
+    /// Notifies the replacer that the frame has been accessed, updating its
+    /// size.
+    ///
+    /// The size of zero is treated as one.
+    pub fn touch_with_size(&self, id: F, size: u64) -> EvictResult<(), F> {
+        self.access(id, Some(size.max(1)))
+    }
+
+    /// Registers access to the frame, optionally updating its size.
+    fn access(&self, id: F, size: Option<u64>) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+        if !inner.pages.contains_key(&id) && inner.pages.len() >= inner.config.capacity {
+            return Err(EvictError::FrameReplacerFull);
+        }
+        inner.time += 1;
+        let now = inner.time;
+
+        // Hits are accounted to the class and age of the page before the access.
+        let hit = inner
+            .pages
+            .get(&id)
+            .map(|page| (page.class(), inner.age(page)));
+        if let Some((class, age)) = hit {
+            inner.classes[class].hits[age] += 1.0;
+        }
+
+        let page = inner.pages.entry(id.clone()).or_insert(PageInfo {
+            last_access: now,
+            hits: 0,
+            size: 1,
+            evictable: true,
+        });
+        if hit.is_some() {
+            page.hits += 1;
+        }
+        page.last_access = now;
+        if let Some(size) = size {
+            page.size = size;
+        }
+        if hit.is_none() {
+            inner.frames.insert(id);
+        }
+
+        if now.is_multiple_of(inner.reconfiguration_interval) {
+            inner.classes.iter_mut().for_each(Class::reconfigure);
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    /// Coarsened age of the page.
+    fn age(&self, page: &PageInfo) -> usize {
+        let age = (self.time - page.last_access) / self.coarsening;
+        (age as usize).min(AGES - 1)
+    }
+
+    /// Hit density of the page.
+    fn density(&self, page: &PageInfo) -> f64 {
+        self.classes[page.class()].densities[self.age(page)] / page.size as f64
+    }
+
+    /// Frame with the lowest hit density among the sampled ones.
+    fn victim(&self, rng: &mut Rng) -> Option<F> {
+        let mut victim: Option<(&F, f64)> = None;
+        for id in self.frames.sample(rng, self.config.samples.max(1)) {
+            let Some(page) = self.pages.get(id) else {
+                continue;
+            };
+            let density = self.density(page);
+            if victim.is_none_or(|(_, lowest)| density < lowest) {
+                victim = Some((id, density));
+            }
+        }
+        victim.map(|(id, _)| id.clone())
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for LhdReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let mut rng = inner.rng.clone();
+        let id = inner.victim(&mut rng)?;
+        inner.rng = rng;
+        inner.frames.remove(&id);
+        let page = inner.pages.remove(&id)?;
+        let (class, age) = (page.class(), inner.age(&page));
+        inner.classes[class].evictions[age] += 1.0;
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        let inner = self.inner.read();
+        // Use a copy of the generator, so that the next eviction picks the same frame.
+        inner.victim(&mut inner.rng.clone())
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        self.access(id, None)
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // LHD does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let page = inner
+            .pages
+            .get_mut(&id)
+            .ok_or(EvictError::InvalidFrameId(id.clone()))?;
+        page.evictable = false;
+        inner.frames.remove(&id);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(page) = inner.pages.get_mut(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+        page.evictable = true;
+        inner.frames.insert(id);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if let Some(page) = inner.pages.get(&id) {
+            if !page.evictable {
+                return Err(EvictError::PinnedFrameRemoval(id));
+            }
+            inner.pages.remove(&id);
+            inner.frames.remove(&id);
+        }
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().frames.len()
+    }
+}
@@ -0,0 +1,388 @@
+//! LIRS page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the [LIRS paper](https://dl.acm.org/doi/10.1145/511399.511340).
+
+use {
+    super::list::FrameList,
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{collections::HashMap, sync::Arc},
+};
+
+/// Configuration of the LIRS replacer.
+#[derive(Debug)]
+pub struct LirsConfig {
+    /// Maximum number of resident frames to keep track of.
+    pub capacity: usize,
+
+    /// Share of the capacity reserved for resident HIR (high inter-reference
+    /// recency) frames. The rest of the capacity is used by LIR frames.
+    ///
+    /// The paper suggests to keep this share small (around 1%), at least one
+    /// frame is always reserved for HIR frames.
+    pub hir_ratio: f64,
+
+    /// Maximum number of non-resident HIR frames kept in the LIRS stack.
+    ///
+    /// Non-resident frames carry no data, only the recency information, which
+    /// is used to promote frames into the LIR set when they are referenced
+    /// again.
+    pub max_non_resident: usize,
+}
+
+impl Default for LirsConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            hir_ratio: 0.01,
+            max_non_resident: 4096,
+        }
+    }
+}
+
+/// Status of a frame tracked by the replacer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// Low inter-reference recency frame (always resident).
+    Lir,
+
+    /// High inter-reference recency frame, resident or not.
+    Hir { resident: bool },
+}
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    status: Status,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+/// Implements the LIRS (Low Inter-reference Recency Set) page replacement
+/// algorithm.
+///
+/// Frames are split into LIR frames, which have been re-referenced within a
+/// short distance and are never evicted directly, and HIR frames. The LIRS
+/// stack `S` holds recency information of LIR frames along with recently
+/// accessed HIR frames (resident or not), while the queue `Q` holds resident
+/// HIR frames in the order of their eviction.
+///
+/// When an HIR frame is re-referenced while still in `S`, its inter-reference
+/// recency is lower than that of the least recent LIR frame, so the two swap
+/// their statuses. This makes the policy resistant to both scans and loops
+/// slightly larger than the replacer's capacity.
+///
+/// Accesses marked by [`AccessType::is_scan`] never change the LIR set: frames
+/// accessed by scans are only queued as resident HIR frames.
+pub struct LirsReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: LirsConfig,
+
+    /// Maximum number of LIR frames.
+    lir_capacity: usize,
+
+    /// Number of LIR frames.
+    lir_count: usize,
+
+    /// Number of resident frames (both pinned and evictable).
+    resident: usize,
+
+    /// Number of evictable frames in the replacer.
+    size: usize,
+
+    /// Mapping of frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    /// LIRS stack `S`, the most recently accessed frame is at the back.
+    stack: FrameList<F>,
+
+    /// Queue `Q` of resident HIR frames, the next victim is at the front.
+    queue: FrameList<F>,
+
+    /// Non-resident HIR frames, in the order of their eviction.
+    non_resident: FrameList<F>,
+}
+
+impl<F: FrameId> Default for LirsReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(LirsConfig::default())
+    }
+}
+
+impl<F: FrameId> LirsReplacer<F> {
+    /// Creates a new LIRS replacer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(LirsConfig {
+            capacity,
+            max_non_resident: capacity,
+            ..LirsConfig::default()
+        })
+    }
+
+    /// Creates a new LIRS replacer with the given configuration.
+    pub fn with_config(config: LirsConfig) -> Self {
+        let capacity = config.capacity;
+        let hir_capacity =
+            ((capacity as f64 * config.hir_ratio).ceil() as usize).clamp(1, capacity.max(1));
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                lir_capacity: capacity.saturating_sub(hir_capacity),
+                lir_count: 0,
+                resident: 0,
+                size: 0,
+                pages: HashMap::with_capacity(capacity),
+                stack: FrameList::with_capacity(capacity),
+                queue: FrameList::with_capacity(hir_capacity),
+                non_resident: FrameList::default(),
+                config,
+            })),
+        }
+    }
+
+    fn access(&self, id: F, scan: bool) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let status = inner.pages.get(&id).map(|page| page.status);
+        match status {
+            Some(Status::Lir) => {
+                if !scan {
+                    inner.stack.push_back(id);
+                    inner.prune();
+                }
+            }
+            Some(Status::Hir { resident: true }) => {
+                if scan {
+                    inner.queue.push_back(id);
+                } else if inner.stack.contains(&id) {
+                    // Re-referenced while in the stack: promote to LIR.
+                    inner.queue.remove(&id);
+                    inner.stack.push_back(id.clone());
+                    inner.promote(id);
+                } else {
+                    inner.stack.push_back(id.clone());
+                    inner.queue.push_back(id);
+                }
+            }
+            Some(Status::Hir { resident: false }) | None => {
+                if inner.resident >= inner.config.capacity {
+                    return Err(EvictError::FrameReplacerFull);
+                }
+                inner.resident += 1;
+                inner.size += 1;
+                inner.non_resident.remove(&id);
+
+                let in_stack = status.is_some();
+                let warm_up = inner.lir_count < inner.lir_capacity;
+                inner.pages.insert(id.clone(), PageInfo {
+                    status: Status::Hir { resident: true },
+                    evictable: true,
+                });
+
+                if scan {
+                    inner.queue.push_back(id);
+                } else if in_stack || warm_up {
+                    // Either the LIR set is not full yet, or the frame has been
+                    // re-referenced while still in the stack.
+                    inner.stack.push_back(id.clone());
+                    inner.promote(id);
+                } else {
+                    inner.stack.push_back(id.clone());
+                    inner.queue.push_back(id);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    /// Finds the next victim.
+    ///
+    /// Resident HIR frames are evicted first. Only if all of them are pinned,
+    /// the least recent LIR frame is considered.
+    fn victim(&self) -> Option<F> {
+        let evictable = |id: &&F| self.pages.get(*id).is_some_and(|page| page.evictable);
+        self.queue.iter().find(evictable).cloned().or_else(|| {
+            self.stack
+                .iter()
+                .filter(|id| {
+                    self.pages
+                        .get(*id)
+                        .is_some_and(|page| page.status == Status::Lir)
+                })
+                .find(evictable)
+                .cloned()
+        })
+    }
+
+    /// Turns a resident HIR frame into a LIR one.
+    ///
+    /// If the LIR set overflows, the least recent LIR frame is demoted.
+    fn promote(&mut self, id: F) {
+        if let Some(page) = self.pages.get_mut(&id) {
+            page.status = Status::Lir;
+            self.lir_count += 1;
+        }
+
+        if self.lir_count > self.lir_capacity
+            && let Some(bottom) = self.stack.pop_front()
+        {
+            if let Some(page) = self.pages.get_mut(&bottom) {
+                page.status = Status::Hir { resident: true };
+                self.lir_count -= 1;
+            }
+            self.queue.push_back(bottom);
+        }
+        self.prune();
+    }
+
+    /// Removes HIR frames from the bottom of the stack, so that the least
+    /// recent frame in the stack is always a LIR one.
+    fn prune(&mut self) {
+        while let Some(bottom) = self.stack.front().cloned() {
+            match self.pages.get(&bottom).map(|page| page.status) {
+                Some(Status::Lir) => break,
+                Some(Status::Hir { resident: false }) => {
+                    self.pages.remove(&bottom);
+                    self.non_resident.remove(&bottom);
+                }
+                _ => {}
+            }
+            self.stack.remove(&bottom);
+        }
+    }
+
+    /// Removes a resident frame from the replacer.
+    ///
+    /// If `keep_history` is set, and the frame is an HIR one still present in
+    /// the stack, it is kept as a non-resident frame.
+    fn remove_resident(&mut self, id: &F, keep_history: bool) {
+        let Some(status) = self.pages.get(id).map(|page| page.status) else {
+            return;
+        };
+        self.resident -= 1;
+        self.size -= 1;
+
+        match status {
+            Status::Lir => {
+                self.pages.remove(id);
+                self.stack.remove(id);
+                self.lir_count -= 1;
+                self.prune();
+            }
+            _ if keep_history && self.stack.contains(id) => {
+                self.queue.remove(id);
+                if let Some(page) = self.pages.get_mut(id) {
+                    page.status = Status::Hir { resident: false };
+                }
+                self.non_resident.push_back(id.clone());
+
+                // Keep the number of non-resident frames within the limit.
+                while self.non_resident.len() > self.config.max_non_resident {
+                    if let Some(oldest) = self.non_resident.pop_front() {
+                        self.pages.remove(&oldest);
+                        self.stack.remove(&oldest);
+                    }
+                }
+            }
+            _ => {
+                self.pages.remove(id);
+                self.queue.remove(id);
+                self.stack.remove(id);
+            }
+        }
+    }
+
+    fn resident_page_mut(&mut self, id: &F) -> Option<&mut PageInfo> {
+        self.pages
+            .get_mut(id)
+            .filter(|page| page.status != Status::Hir { resident: false })
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for LirsReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let id = inner.victim()?;
+        inner.remove_resident(&id, true);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        self.inner.read().victim()
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        self.access(id, false)
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, access_type: T) -> EvictResult<(), F> {
+        self.access(id, access_type.is_scan())
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let page = inner
+            .resident_page_mut(&id)
+            .ok_or(EvictError::InvalidFrameId(id))?;
+
+        // No-op if the frame is already in the desired state.
+        if !page.evictable {
+            return Ok(());
+        }
+
+        page.evictable = false;
+        inner.size -= 1;
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(page) = inner.resident_page_mut(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+
+        // No-op if the frame is already in the desired state.
+        if page.evictable {
+            return Ok(());
+        }
+
+        page.evictable = true;
+        inner.size += 1;
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if let Some(page) = inner.resident_page_mut(&id) {
+            if !page.evictable {
+                return Err(EvictError::PinnedFrameRemoval(id));
+            }
+            inner.remove_resident(&id, false);
+        }
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().size
+    }
+}
@@ -0,0 +1,260 @@
+//! Doubly-linked list of frame identifiers.
+//!
+//! Most of the list-based policies (ARC, 2Q, LIRS etc) need to move frames
+//! between the ends of several lists, remove frames from the middle of a list
+//! and walk the list in order. The [`FrameList`] provides all of these
+//! operations in O(1), by keeping list nodes in a slab and indexing them by
+//! frame identifier.
+
+use {
+    crate::FrameId,
+    std::collections::{HashMap, VecDeque},
+};
+
+/// Sentinel value used as a "null" link.
+const NIL: usize = usize::MAX;
+
+/// List node.
+#[derive(Debug)]
+struct Node<F> {
+    id: F,
+    prev: usize,
+    next: usize,
+}
+
+/// Ordered list of unique frame identifiers.
+///
+/// The front of the list is its oldest (least recently inserted) end, while
+/// the back is the newest one.
+#[derive(Debug)]
+pub(crate) struct FrameList<F: FrameId> {
+    /// Slab of list nodes. Slots of removed nodes are reused.
+    nodes: Vec<Option<Node<F>>>,
+
+    /// Slots which can be reused for new nodes.
+    free: Vec<usize>,
+
+    /// Mapping of frame IDs to their slots in `nodes`.
+    index: HashMap<F, usize>,
+
+    head: usize,
+    tail: usize,
+}
+
+impl<F: FrameId> Default for FrameList<F> {
+    fn default() -> Self {
+        Self::with_capacity(0)
+    }
+}
+
+impl<F: FrameId> FrameList<F> {
+    /// Creates an empty list with pre-allocated space for `capacity` frames.
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            nodes: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            index: HashMap::with_capacity(capacity),
+            head: NIL,
+            tail: NIL,
+        }
+    }
+
+    /// Number of frames in the list.
+    pub(crate) fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Whether the list is empty.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Whether the frame is in the list.
+    pub(crate) fn contains(&self, id: &F) -> bool {
+        self.index.contains_key(id)
+    }
+
+    /// The oldest frame in the list.
+    pub(crate) fn front(&self) -> Option<&F> {
+        self.node(self.head).map(|node| &node.id)
+    }
+
+    /// The newest frame in the list.
+    pub(crate) fn back(&self) -> Option<&F> {
+        self.node(self.tail).map(|node| &node.id)
+    }
+
+    /// The frame following `id` (towards the back of the list).
+    pub(crate) fn next(&self, id: &F) -> Option<&F> {
+        let slot = *self.index.get(id)?;
+        self.node(self.node(slot)?.next).map(|node| &node.id)
+    }
+
+    /// Appends the frame to the back of the list.
+    ///
+    /// If the frame is already in the list, it is moved to the back.
+    pub(crate) fn push_back(&mut self, id: F) {
+        self.remove(&id);
+        let slot = self.alloc(Node {
+            id: id.clone(),
+            prev: self.tail,
+            next: NIL,
+        });
+        match self.node_mut(self.tail) {
+            Some(tail) => tail.next = slot,
+            None => self.head = slot,
+        }
+        self.tail = slot;
+        self.index.insert(id, slot);
+    }
+
+    /// Prepends the frame to the front of the list.
+    ///
+    /// If the frame is already in the list, it is moved to the front.
+    pub(crate) fn push_front(&mut self, id: F) {
+        self.remove(&id);
+        let slot = self.alloc(Node {
+            id: id.clone(),
+            prev: NIL,
+            next: self.head,
+        });
+        match self.node_mut(self.head) {
+            Some(head) => head.prev = slot,
+            None => self.tail = slot,
+        }
+        self.head = slot;
+        self.index.insert(id, slot);
+    }
+
+    /// Removes and returns the oldest frame.
+    pub(crate) fn pop_front(&mut self) -> Option<F> {
+        let id = self.front()?.clone();
+        self.remove(&id);
+        Some(id)
+    }
+
+    /// Removes and returns the newest frame.
+    pub(crate) fn pop_back(&mut self) -> Option<F> {
+        let id = self.back()?.clone();
+        self.remove(&id);
+        Some(id)
+    }
+
+    /// Removes the frame from the list.
+    ///
+    /// Returns `false` if the frame was not in the list.
+    pub(crate) fn remove(&mut self, id: &F) -> bool {
+        let Some(slot) = self.index.remove(id) else {
+            return false;
+        };
+        let Some(node) = self.nodes[slot].take() else {
+            return false;
+        };
+        match self.node_mut(node.prev) {
+            Some(prev) => prev.next = node.next,
+            None => self.head = node.next,
+        }
+        match self.node_mut(node.next) {
+            Some(next) => next.prev = node.prev,
+            None => self.tail = node.prev,
+        }
+        self.free.push(slot);
+        true
+    }
+
+    /// Iterates over frames, from the front to the back of the list.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &F> {
+        let mut slot = self.head;
+        std::iter::from_fn(move || {
+            let node = self.node(slot)?;
+            slot = node.next;
+            Some(&node.id)
+        })
+    }
+
+    /// Iterates over frames, from the back to the front of the list.
+    pub(crate) fn iter_rev(&self) -> impl Iterator<Item = &F> {
+        let mut slot = self.tail;
+        std::iter::from_fn(move || {
+            let node = self.node(slot)?;
+            slot = node.prev;
+            Some(&node.id)
+        })
+    }
+
+    fn alloc(&mut self, node: Node<F>) -> usize {
+        match self.free.pop() {
+            Some(slot) => {
+                self.nodes[slot] = Some(node);
+                slot
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn node(&self, slot: usize) -> Option<&Node<F>> {
+        self.nodes.get(slot).and_then(Option::as_ref)
+    }
+
+    fn node_mut(&mut self, slot: usize) -> Option<&mut Node<F>> {
+        self.nodes.get_mut(slot).and_then(Option::as_mut)
+    }
+}
+
+/// Queue on top of a [`FrameList`], which takes frames from the front of the
+/// list and appends frames to its back without modifying the list.
+///
+/// Used to simulate queue rotations (e.g. clock hands) while holding a read
+/// lock only: frames are never taken from the middle of the list, so the list
+/// frames still in the queue are always the ones following `front`.
+pub(crate) struct QueueView<'a, F: FrameId> {
+    list: &'a FrameList<F>,
+
+    /// The first frame of the list still in the queue.
+    front: Option<&'a F>,
+
+    /// Number of frames of the list still in the queue.
+    remaining: usize,
+
+    /// Frames appended to the queue.
+    back: VecDeque<F>,
+}
+
+impl<'a, F: FrameId> QueueView<'a, F> {
+    pub(crate) fn new(list: &'a FrameList<F>) -> Self {
+        Self {
+            list,
+            front: list.front(),
+            remaining: list.len(),
+            back: VecDeque::new(),
+        }
+    }
+
+    /// Number of frames in the queue.
+    pub(crate) fn len(&self) -> usize {
+        self.remaining + self.back.len()
+    }
+
+    /// The oldest frame in the queue.
+    pub(crate) fn front(&self) -> Option<&F> {
+        self.front.or_else(|| self.back.front())
+    }
+
+    /// Appends the frame to the back of the queue.
+    pub(crate) fn push_back(&mut self, id: F) {
+        self.back.push_back(id);
+    }
+
+    /// Removes and returns the oldest frame.
+    pub(crate) fn pop_front(&mut self) -> Option<F> {
+        let Some(id) = self.front else {
+            return self.back.pop_front();
+        };
+        self.front = self.list.next(id);
+        self.remaining -= 1;
+        Some(id.clone())
+    }
+}
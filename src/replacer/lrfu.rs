@@ -0,0 +1,265 @@
+//! LRFU page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the "LRFU: A Spectrum of Policies
+//! that Subsumes the Least Recently Used and Least Frequently Used Policies"
+//! paper by Lee et al.
+
+use {
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    hlc_gen::{HlcGenerator, HlcTimestamp},
+    parking_lot::RwLock,
+    priority_queue::PriorityQueue,
+    std::{cmp::Reverse, collections::HashMap, sync::Arc},
+};
+
+/// Configuration of the LRFU replacer.
+#[derive(Debug)]
+pub struct LrfuConfig {
+    /// Maximum number of frames to keep track of.
+    pub capacity: usize,
+
+    /// Decay rate of the weighing function `F(x) = (1/2)^(lambda * x)`, where
+    /// `x` is the time (in milliseconds) elapsed since a reference.
+    ///
+    /// With `lambda` of 0, every reference contributes equally, so the
+    /// replacer works as LFU. The larger `lambda` is, the faster the
+    /// contribution of older references fades, and the closer the replacer
+    /// gets to LRU.
+    pub lambda: f64,
+}
+
+impl Default for LrfuConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            lambda: 0.001,
+        }
+    }
+}
+
+/// Eviction priority of a page.
+///
+/// Since the CRF values of all the pages decay at the same rate, their
+/// relative order does not change over time. So, instead of the CRF value
+/// itself, time-invariant `log2(CRF(t)) + lambda * t` is stored, where `t` is
+/// the time of the last reference. Ties are broken by the timestamp of the
+/// last reference.
+#[derive(Debug, Clone, Copy)]
+struct Score {
+    value: f64,
+    timestamp: HlcTimestamp,
+}
+
+impl PartialEq for Score {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.value
+            .total_cmp(&other.value)
+            .then_with(|| self.timestamp.cmp(&other.timestamp))
+    }
+}
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    /// Combined Recency and Frequency value, as of the last reference.
+    crf: f64,
+
+    /// Timestamp of the last reference.
+    last_ref: HlcTimestamp,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+impl PageInfo {
+    /// Score of the page, with time measured from the `epoch`.
+    fn score(&self, epoch: HlcTimestamp, lambda: f64) -> Score {
+        let elapsed = (self.last_ref - epoch) as f64;
+        Score {
+            value: self.crf.log2() + lambda * elapsed,
+            timestamp: self.last_ref,
+        }
+    }
+}
+
+/// Least Recently/Frequently Used (LRFU) frame replacer.
+///
+/// Every reference to a page contributes to its Combined Recency and
+/// Frequency (CRF) value, with the weight decaying exponentially with the age
+/// of the reference (see [`LrfuConfig::lambda`]). The page with the smallest
+/// CRF value is evicted first.
+///
+/// The CRF value is updated incrementally on every access, and pages are kept
+/// in a priority queue ordered by their (time-invariant) scores, so neither
+/// `touch` nor `evict` has to scan the pages.
+///
+/// Pinned frames are taken out of the queue, but keep their CRF values.
+pub struct LrfuReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: LrfuConfig,
+
+    /// Mapping of frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    /// Evictable frames, ordered by their scores.
+    queue: PriorityQueue<F, Reverse<Score>>,
+
+    /// Monotonically increasing sequence of timestamps.
+    /// Used to determine the order and time of page accesses.
+    seq: HlcGenerator,
+
+    /// Timestamp the scores are measured from (keeps the scores small).
+    epoch: HlcTimestamp,
+}
+
+impl<F: FrameId> Default for LrfuReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(LrfuConfig::default())
+    }
+}
+
+impl<F: FrameId> LrfuReplacer<F> {
+    /// Creates a new LRFU replacer with the given capacity and `lambda`.
+    pub fn new(capacity: usize, lambda: f64) -> Self {
+        Self::with_config(LrfuConfig { capacity, lambda })
+    }
+
+    /// Creates a new LRFU replacer with the given configuration.
+    pub fn with_config(config: LrfuConfig) -> Self {
+        let capacity = config.capacity;
+        let seq = HlcGenerator::default();
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                config,
+                pages: HashMap::with_capacity(capacity),
+                queue: PriorityQueue::with_capacity(capacity),
+                epoch: seq.next_timestamp().unwrap_or_default(),
+                seq,
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for LrfuReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let (id, _) = inner.queue.pop()?;
+        inner.pages.remove(&id);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        self.inner.read().queue.peek().map(|(id, _)| id.clone())
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // The replacer is full, cannot add new page.
+        if inner.pages.len() >= inner.config.capacity && !inner.pages.contains_key(&id) {
+            return Err(EvictError::FrameReplacerFull);
+        }
+
+        let timestamp = inner
+            .seq
+            .next_timestamp()
+            .ok_or(EvictError::SequenceExhausted)?;
+        let (epoch, lambda) = (inner.epoch, inner.config.lambda);
+
+        // CRF(t) = F(0) + F(t - t_last) * CRF(t_last)
+        let page = inner.pages.entry(id.clone()).or_insert(PageInfo {
+            crf: 0.0,
+            last_ref: timestamp,
+            evictable: true,
+        });
+        let elapsed = (timestamp - page.last_ref) as f64;
+        page.crf = 1.0 + 0.5f64.powf(lambda * elapsed) * page.crf;
+        page.last_ref = timestamp;
+
+        if page.evictable {
+            let score = page.score(epoch, lambda);
+            inner.queue.push(id, Reverse(score));
+        }
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // LRFU does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let page = inner
+            .pages
+            .get_mut(&id)
+            .ok_or(EvictError::InvalidFrameId(id.clone()))?;
+        page.evictable = false;
+        inner.queue.remove(&id);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+        let (epoch, lambda) = (inner.epoch, inner.config.lambda);
+
+        // Unpinning unknown frame registers it.
+        let Some(page) = inner.pages.get_mut(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+
+        // No-op if the frame is already in the desired state.
+        if std::mem::replace(&mut page.evictable, true) {
+            return Ok(());
+        }
+        let score = page.score(epoch, lambda);
+        inner.queue.push(id, Reverse(score));
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if let Some(page) = inner.pages.get(&id) {
+            if !page.evictable {
+                return Err(EvictError::PinnedFrameRemoval(id));
+            }
+            inner.pages.remove(&id);
+            inner.queue.remove(&id);
+        }
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().queue.len()
+    }
+}
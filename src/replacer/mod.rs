@@ -1,7 +1,66 @@
+mod aging;
+mod arc;
+mod belady;
+mod car;
+mod clock;
+mod clock_pro;
+mod eelru;
+mod fbr;
+mod fifo;
+mod gclock;
+mod gdsf;
+mod hyperbolic;
+mod lecar;
+mod lfu;
+mod lhd;
+mod lirs;
+mod list;
+mod lrfu;
 mod lru;
 mod lru_k;
+mod mq;
+mod mru;
+mod random;
+mod rng;
+mod rrip;
+mod s3_fifo;
+mod sample;
+mod sampled;
+mod sieve;
+mod slru;
+mod two_q;
+mod w_tiny_lfu;
+mod ws_clock;
 
 pub use {
+    aging::{AgingConfig, AgingReplacer},
+    arc::ArcReplacer,
+    belady::BeladyReplacer,
+    car::{CarConfig, CarReplacer},
+    clock::{ClockReplacer, DenseClockReplacer},
+    clock_pro::ClockProReplacer,
+    eelru::{EelruConfig, EelruReplacer},
+    fbr::{FbrConfig, FbrReplacer},
+    fifo::FifoReplacer,
+    gclock::{GclockConfig, GclockReplacer},
+    gdsf::GdsfReplacer,
+    hyperbolic::{HyperbolicConfig, HyperbolicReplacer},
+    lecar::{LecarConfig, LecarReplacer},
+    lfu::{LfuConfig, LfuReplacer},
+    lhd::{LhdConfig, LhdReplacer},
+    lirs::{LirsConfig, LirsReplacer},
+    lrfu::{LrfuConfig, LrfuReplacer},
     lru::LruReplacer,
     lru_k::{LRUK_REPLACER_K, LRUK_REPLACER_REF_PERIOD, LruKConfig, LruKReplacer},
+    mq::{MqConfig, MqReplacer},
+    mru::MruReplacer,
+    random::RandomReplacer,
+    rrip::{DenseRripReplacer, RripConfig, RripInsertion, RripReplacer},
+    s3_fifo::{S3FifoConfig, S3FifoReplacer},
+    sampled::{SampledConfig, SampledPolicy, SampledReplacer},
+    sieve::SieveReplacer,
+    slru::{SlruConfig, SlruReplacer},
+    two_q::{TwoQConfig, TwoQReplacer},
+    w_tiny_lfu::{WTinyLfuConfig, WTinyLfuReplacer},
+    ws_clock::{WsClockConfig, WsClockReplacer},
 };
@@ -0,0 +1,328 @@
+//! MQ page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the "The Multi-Queue Replacement
+//! Algorithm for Second Level Buffer Caches" paper by Zhou et al.
+
+use {
+    super::list::FrameList,
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{collections::HashMap, sync::Arc},
+};
+
+/// Configuration of the MQ replacer.
+#[derive(Debug)]
+pub struct MqConfig {
+    /// Maximum number of resident frames.
+    pub capacity: usize,
+
+    /// Number of LRU queues (`m` in the MQ paper), at least one.
+    ///
+    /// A frame accessed `f` times resides in the queue `log2(f)`, so the last
+    /// queue holds frames accessed at least `2^(m - 1)` times.
+    pub queues: usize,
+
+    /// Number of accesses (to any frame) a frame stays in its queue for, if it
+    /// is not accessed itself. Once its life time expires, the frame is
+    /// demoted into the previous queue.
+    pub life_time: u64,
+
+    /// Maximum number of identifiers in the history buffer (`Qout`), relative
+    /// to the capacity.
+    pub history_ratio: f64,
+}
+
+impl Default for MqConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            queues: 8,
+            life_time: 4096,
+            history_ratio: 4.0,
+        }
+    }
+}
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    /// Number of accesses to the page, including the ones made before it was
+    /// evicted last time (if it is still remembered in the history).
+    freq: u64,
+
+    /// Index of the queue the page resides in.
+    queue: usize,
+
+    /// Time the page is demoted at, unless it is accessed again.
+    expire: u64,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+/// Multi-Queue (MQ) frame replacer.
+///
+/// Resident frames are kept in `m` LRU queues (see [`MqConfig::queues`]), a
+/// frame accessed `f` times resides in the queue `log2(f)`. The victim is
+/// the least recently used frame of the lowest non-empty queue, so frequently
+/// accessed frames outlive the rest, even if they are not accessed recently.
+///
+/// To prevent frames which were hot long ago from staying forever, every
+/// frame has a life time (see [`MqConfig::life_time`]): if a frame is not
+/// accessed for that long, it is demoted into the previous queue.
+///
+/// Access counts of the evicted frames are remembered in the history buffer
+/// (`Qout`), so that a frame coming back is put straight into its former
+/// queue. This makes MQ well suited for second level buffer caches, which see
+/// the accesses missed by the first level cache: the reuse distances there
+/// are long, so recency is a poor predictor, but frequency is still a good
+/// one.
+///
+/// Pinned frames stay in their queues (and are demoted as usual), but are
+/// skipped on eviction.
+pub struct MqReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: MqConfig,
+
+    /// Current time, incremented on every access.
+    time: u64,
+
+    /// Maximum number of identifiers in the history buffer.
+    history_limit: usize,
+
+    /// LRU queues, the least recently used frame is at the front.
+    queues: Vec<FrameList<F>>,
+
+    /// Mapping of resident frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    /// History buffer, the least recently evicted frame is at the front.
+    history: FrameList<F>,
+
+    /// Access counts of the frames in the history buffer.
+    history_freq: HashMap<F, u64>,
+
+    /// Number of evictable frames in the replacer.
+    size: usize,
+}
+
+impl<F: FrameId> Default for MqReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(MqConfig::default())
+    }
+}
+
+impl<F: FrameId> MqReplacer<F> {
+    /// Creates a new MQ replacer with the given capacity.
+    ///
+    /// The life time of frames is set to the capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(MqConfig {
+            capacity,
+            life_time: capacity as u64,
+            ..MqConfig::default()
+        })
+    }
+
+    /// Creates a new MQ replacer with the given configuration.
+    pub fn with_config(config: MqConfig) -> Self {
+        let capacity = config.capacity;
+        let queues = config.queues.max(1);
+        let history_limit = (capacity as f64 * config.history_ratio) as usize;
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                config,
+                time: 0,
+                history_limit,
+                queues: (0..queues).map(|_| FrameList::default()).collect(),
+                pages: HashMap::with_capacity(capacity),
+                history: FrameList::with_capacity(history_limit),
+                history_freq: HashMap::with_capacity(history_limit),
+                size: 0,
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    /// Index of the queue for a page accessed `freq` times.
+    fn queue(&self, freq: u64) -> usize {
+        (freq.max(1).ilog2() as usize).min(self.queues.len() - 1)
+    }
+
+    /// The least recently used evictable page of the lowest non-empty queue.
+    fn victim(&self) -> Option<F> {
+        if self.size == 0 {
+            return None;
+        }
+        self.queues.iter().find_map(|queue| {
+            queue
+                .iter()
+                .find(|id| self.pages.get(*id).is_some_and(|page| page.evictable))
+                .cloned()
+        })
+    }
+
+    /// Advances the time, and demotes the pages whose life time has expired.
+    ///
+    /// Only the least recently used page of every queue is checked, as it is
+    /// the one to expire first.
+    fn adjust(&mut self) {
+        self.time += 1;
+        for queue in 1..self.queues.len() {
+            let Some(id) = self.queues[queue].front().cloned() else {
+                continue;
+            };
+            let Some(page) = self.pages.get_mut(&id) else {
+                continue;
+            };
+            if page.expire >= self.time {
+                continue;
+            }
+            page.queue = queue - 1;
+            page.expire = self.time + self.config.life_time;
+            self.queues[queue].remove(&id);
+            self.queues[queue - 1].push_back(id);
+        }
+    }
+
+    /// Remembers access count of the evicted page.
+    fn remember(&mut self, id: F, freq: u64) {
+        if self.history_limit == 0 {
+            return;
+        }
+        if self.history.len() >= self.history_limit
+            && let Some(oldest) = self.history.pop_front()
+        {
+            self.history_freq.remove(&oldest);
+        }
+        self.history.push_back(id.clone());
+        self.history_freq.insert(id, freq);
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for MqReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let id = inner.victim()?;
+        let page = inner.pages.remove(&id)?;
+        inner.queues[page.queue].remove(&id);
+        inner.size -= 1;
+        inner.remember(id.clone(), page.freq);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        self.inner.read().victim()
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let freq = match inner.pages.get(&id).map(|page| (page.queue, page.freq)) {
+            Some((queue, freq)) => {
+                inner.queues[queue].remove(&id);
+                freq + 1
+            }
+            None => {
+                if inner.pages.len() >= inner.config.capacity {
+                    return Err(EvictError::FrameReplacerFull);
+                }
+                // Frames coming back from the history restore their access counts.
+                let freq = inner.history_freq.remove(&id).unwrap_or_default();
+                inner.history.remove(&id);
+                inner.size += 1;
+                freq + 1
+            }
+        };
+
+        let queue = inner.queue(freq);
+        let expire = inner.time + inner.config.life_time;
+        let page = inner.pages.entry(id.clone()).or_insert(PageInfo {
+            freq,
+            queue,
+            expire,
+            evictable: true,
+        });
+        page.freq = freq;
+        page.queue = queue;
+        page.expire = expire;
+        inner.queues[queue].push_back(id);
+        inner.adjust();
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // MQ does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let page = inner
+            .pages
+            .get_mut(&id)
+            .ok_or(EvictError::InvalidFrameId(id))?;
+
+        // No-op if the frame is already in the desired state.
+        if !std::mem::replace(&mut page.evictable, false) {
+            return Ok(());
+        }
+        inner.size -= 1;
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(page) = inner.pages.get_mut(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+
+        // No-op if the frame is already in the desired state.
+        if std::mem::replace(&mut page.evictable, true) {
+            return Ok(());
+        }
+        inner.size += 1;
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let Some(page) = inner.pages.get(&id) else {
+            return Ok(());
+        };
+        if !page.evictable {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+
+        // Removed frames are not remembered in the history.
+        let queue = page.queue;
+        inner.queues[queue].remove(&id);
+        inner.pages.remove(&id);
+        inner.size -= 1;
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().size
+    }
+}
@@ -0,0 +1,131 @@
+//! MRU page replacement algorithm.
+
+use {
+    super::list::FrameList,
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{collections::HashSet, sync::Arc},
+};
+
+/// Most Recently Used (MRU) frame replacer.
+///
+/// The most recently accessed frame is evicted first. This is the opposite of
+/// [`LruReplacer`](crate::LruReplacer), and works best for cyclic access
+/// patterns over a working set larger than the replacer (e.g. repeated
+/// sequential scans), where the most recently accessed frame is the one that
+/// will be needed again the latest.
+///
+/// Pinned frames keep their positions in the list, but are never picked as
+/// victims.
+pub struct MruReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Maximum number of frames that can be stored in the replacer.
+    capacity: usize,
+
+    /// Frames in the order of their accesses, the most recently accessed frame
+    /// is at the back.
+    queue: FrameList<F>,
+
+    /// Frames which are not candidates for eviction.
+    pinned: HashSet<F>,
+}
+
+impl<F: FrameId> MruReplacer<F> {
+    /// Creates a new MRU replacer.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                capacity,
+                queue: FrameList::with_capacity(capacity),
+                pinned: HashSet::new(),
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    fn victim(&self) -> Option<F> {
+        self.queue
+            .iter_rev()
+            .find(|id| !self.pinned.contains(*id))
+            .cloned()
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for MruReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let id = inner.victim()?;
+        inner.queue.remove(&id);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        self.inner.read().victim()
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if !inner.queue.contains(&id) && inner.queue.len() >= inner.capacity {
+            return Err(EvictError::FrameReplacerFull);
+        }
+        inner.queue.push_back(id);
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // MRU does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if !inner.queue.contains(&id) {
+            return Err(EvictError::InvalidFrameId(id));
+        }
+        inner.pinned.insert(id);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        if !inner.queue.contains(&id) {
+            drop(inner);
+            return self.touch(id);
+        }
+        inner.pinned.remove(&id);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if inner.pinned.contains(&id) {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+        inner.queue.remove(&id);
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().capacity
+    }
+
+    fn size(&self) -> usize {
+        let inner = self.inner.read();
+        inner.queue.len() - inner.pinned.len()
+    }
+}
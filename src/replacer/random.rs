@@ -0,0 +1,169 @@
+//! Random page replacement algorithm.
+
+use {
+    super::rng::Rng,
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    },
+};
+
+/// Random frame replacer.
+///
+/// Victims are picked uniformly at random among the evictable frames.
+/// Accessing a frame does not affect the eviction order.
+///
+/// The pseudo-random generator is seeded explicitly, so that the same sequence
+/// of operations always results in the same sequence of victims.
+pub struct RandomReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Maximum number of frames that can be stored in the replacer.
+    capacity: usize,
+
+    /// Evictable frames, in no particular order.
+    frames: Vec<F>,
+
+    /// Mapping of evictable frame IDs to their positions in `frames`.
+    index: HashMap<F, usize>,
+
+    /// Frames which are not candidates for eviction.
+    pinned: HashSet<F>,
+
+    rng: Rng,
+}
+
+impl<F: FrameId> RandomReplacer<F> {
+    /// Creates a new Random replacer, with the pseudo-random generator
+    /// initialized with the given seed.
+    pub fn new(capacity: usize, seed: u64) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                capacity,
+                frames: Vec::with_capacity(capacity),
+                index: HashMap::with_capacity(capacity),
+                pinned: HashSet::new(),
+                rng: Rng::new(seed),
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    fn contains(&self, id: &F) -> bool {
+        self.index.contains_key(id) || self.pinned.contains(id)
+    }
+
+    fn insert(&mut self, id: F) {
+        self.index.insert(id.clone(), self.frames.len());
+        self.frames.push(id);
+    }
+
+    /// Removes the evictable frame, returning `false` if it is not found.
+    fn detach(&mut self, id: &F) -> bool {
+        let Some(pos) = self.index.remove(id) else {
+            return false;
+        };
+        self.frames.swap_remove(pos);
+        if let Some(moved) = self.frames.get(pos) {
+            self.index.insert(moved.clone(), pos);
+        }
+        true
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for RandomReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        if inner.frames.is_empty() {
+            return None;
+        }
+        let len = inner.frames.len();
+        let pos = inner.rng.below(len);
+        let id = inner.frames[pos].clone();
+        inner.detach(&id);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        let inner = self.inner.read();
+        if inner.frames.is_empty() {
+            return None;
+        }
+        // Use a copy of the generator, so that the next eviction picks the same frame.
+        let pos = inner.rng.clone().below(inner.frames.len());
+        inner.frames.get(pos).cloned()
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if inner.contains(&id) {
+            return Ok(());
+        }
+        if inner.frames.len() + inner.pinned.len() >= inner.capacity {
+            return Err(EvictError::FrameReplacerFull);
+        }
+        inner.insert(id);
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // Random does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if !inner.contains(&id) {
+            return Err(EvictError::InvalidFrameId(id));
+        }
+        if inner.detach(&id) {
+            inner.pinned.insert(id);
+        }
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        if !inner.contains(&id) {
+            drop(inner);
+            return self.touch(id);
+        }
+        if inner.pinned.remove(&id) {
+            inner.insert(id);
+        }
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if inner.pinned.contains(&id) {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+        inner.detach(&id);
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().frames.len()
+    }
+}
@@ -0,0 +1,682 @@
+//! RRIP page replacement algorithms.
+//!
+//! The algorithms implemented here are based on the [RRIP paper](https://dl.acm.org/doi/10.1145/1815961.1815971).
+
+use {
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::{Mutex, RwLock},
+    std::{
+        collections::HashMap,
+        hash::{DefaultHasher, Hasher},
+        sync::{
+            Arc,
+            atomic::{AtomicU8, Ordering},
+        },
+    },
+};
+
+/// One of that many frames is sampled for set dueling.
+const DUEL_SAMPLING: u64 = 32;
+
+/// Maximum value of the policy selection counter (it is a 10-bit counter).
+const PSEL_MAX: u16 = 1023;
+
+/// With bimodal insertion, one of that many frames is inserted with a long
+/// (rather than distant) re-reference interval.
+const BIMODAL_PERIOD: u32 = 32;
+
+/// Insertion policy of the RRIP replacer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RripInsertion {
+    /// Static RRIP (SRRIP): new frames are predicted to be re-referenced in a
+    /// long interval, so they have to be re-referenced soon to stay. Resistant
+    /// to scans.
+    Static,
+
+    /// Bimodal RRIP (BRRIP): most new frames are predicted to be re-referenced
+    /// in a distant interval, and only few in a long one. Resistant to
+    /// thrashing, i.e. to cyclic access patterns over a working set larger than
+    /// the replacer.
+    Bimodal,
+
+    /// Dynamic RRIP (DRRIP): set dueling between SRRIP and BRRIP picks the
+    /// insertion policy.
+    ///
+    /// Accesses to a sampled group of frames (one of 32) are replayed on two
+    /// leader directories, which are small RRIP replacers holding just the
+    /// tags, using either SRRIP or BRRIP insertion. The policy with fewer
+    /// misses in its leader directory is used for all the frames.
+    Dynamic,
+}
+
+/// Configuration of the RRIP replacers.
+#[derive(Debug)]
+pub struct RripConfig {
+    /// Maximum number of frames that can be stored in the replacer.
+    pub capacity: usize,
+
+    /// Number of bits of the re-reference prediction value (`M` in the RRIP
+    /// paper), clamped to `1..=8`.
+    ///
+    /// With a single bit, SRRIP works as Clock.
+    pub bits: u8,
+
+    /// Insertion policy.
+    pub insertion: RripInsertion,
+}
+
+impl Default for RripConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            bits: 2,
+            insertion: RripInsertion::Dynamic,
+        }
+    }
+}
+
+/// State of a slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// No frame is stored in the slot.
+    Empty,
+
+    /// Frame is stored, but is not a candidate for eviction.
+    Pinned,
+
+    /// Frame is stored, and can be evicted.
+    Evictable,
+}
+
+/// Slot of the frame table.
+#[derive(Debug)]
+struct Slot {
+    /// Re-reference prediction value (RRPV): the higher it is, the later the
+    /// frame is expected to be re-referenced.
+    ///
+    /// The value is atomic, so that it can be reset while holding a read lock.
+    rrpv: AtomicU8,
+
+    state: State,
+}
+
+/// Fixed array of slots with a rotating hand.
+///
+/// Shared by both [`RripReplacer`] and [`DenseRripReplacer`], which differ
+/// only in the way frame IDs are mapped to slots, and by the leader
+/// directories of DRRIP.
+#[derive(Debug)]
+struct Rrip {
+    slots: Vec<Slot>,
+
+    /// Position the search for a victim starts from.
+    hand: usize,
+
+    /// Number of evictable frames.
+    size: usize,
+
+    /// Maximum RRPV, i.e. the distant re-reference interval.
+    distant: u8,
+}
+
+impl Rrip {
+    fn new(capacity: usize, bits: u8) -> Self {
+        Self {
+            slots: (0..capacity)
+                .map(|_| Slot {
+                    rrpv: AtomicU8::new(0),
+                    state: State::Empty,
+                })
+                .collect(),
+            hand: 0,
+            size: 0,
+            distant: u8::MAX >> (8 - bits.clamp(1, 8)),
+        }
+    }
+
+    fn state(&self, slot: usize) -> State {
+        self.slots.get(slot).map_or(State::Empty, |s| s.state)
+    }
+
+    fn rrpv(&self, slot: usize) -> u8 {
+        self.slots[slot].rrpv.load(Ordering::Relaxed)
+    }
+
+    /// Predicts near-immediate re-reference of the frame stored in the slot.
+    fn reference(&self, slot: usize, scan: bool) {
+        // Hits during scans do not promote frames.
+        if !scan {
+            self.slots[slot].rrpv.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// RRPV of a new frame, inserted with the given policy.
+    ///
+    /// Frames accessed during scans are always predicted to be re-referenced
+    /// in a distant interval.
+    fn insertion_rrpv(&self, bimodal: bool, bimodal_count: &mut u32, scan: bool) -> u8 {
+        if scan {
+            return self.distant;
+        }
+        if bimodal {
+            *bimodal_count = bimodal_count.wrapping_add(1);
+            if !bimodal_count.is_multiple_of(BIMODAL_PERIOD) {
+                return self.distant;
+            }
+        }
+        self.distant.saturating_sub(1)
+    }
+
+    /// Stores a new frame with the given RRPV in the empty slot.
+    fn insert(&mut self, slot: usize, rrpv: u8) {
+        let s = &mut self.slots[slot];
+        *s.rrpv.get_mut() = rrpv;
+        s.state = State::Evictable;
+        self.size += 1;
+    }
+
+    fn set_state(&mut self, slot: usize, state: State) {
+        let s = &mut self.slots[slot];
+        match (s.state, state) {
+            (State::Evictable, State::Pinned | State::Empty) => self.size -= 1,
+            (State::Pinned | State::Empty, State::Evictable) => self.size += 1,
+            _ => {}
+        }
+        s.state = state;
+    }
+
+    /// Finds the victim, without aging the frames.
+    ///
+    /// The victim is the first evictable frame (starting from the hand) with
+    /// the highest RRPV. Eviction ages all the frames until its RRPV becomes
+    /// distant, so this is the frame the search would stop at.
+    fn victim(&self) -> Option<usize> {
+        if self.size == 0 {
+            return None;
+        }
+        let n = self.slots.len();
+        let evictable = (0..n)
+            .map(|i| (self.hand + i) % n)
+            .filter(|&slot| self.slots[slot].state == State::Evictable);
+
+        let mut victim: Option<(usize, u8)> = None;
+        for slot in evictable {
+            let rrpv = self.rrpv(slot);
+            if rrpv >= self.distant {
+                return Some(slot);
+            }
+            if victim.is_none_or(|(_, highest)| rrpv > highest) {
+                victim = Some((slot, rrpv));
+            }
+        }
+        victim.map(|(slot, _)| slot)
+    }
+
+    /// Finds the victim, ages the frames, and empties the victim's slot.
+    fn evict(&mut self) -> Option<usize> {
+        let victim = self.victim()?;
+
+        // Age all the frames, so that the victim's RRPV becomes distant.
+        let age = self.distant.saturating_sub(self.rrpv(victim));
+        if age > 0 {
+            for s in self.slots.iter_mut().filter(|s| s.state != State::Empty) {
+                let rrpv = s.rrpv.get_mut();
+                *rrpv = rrpv.saturating_add(age).min(self.distant);
+            }
+        }
+
+        self.hand = (victim + 1) % self.slots.len();
+        self.set_state(victim, State::Empty);
+        Some(victim)
+    }
+}
+
+/// Leader directory of DRRIP, simulating one of the insertion policies on the
+/// sampled frames.
+#[derive(Debug)]
+struct Leader {
+    rrip: Rrip,
+
+    /// Whether the leader uses bimodal (rather than static) insertion.
+    bimodal: bool,
+
+    /// Number of tags inserted with bimodal insertion.
+    bimodal_count: u32,
+
+    /// Mapping of tags (sampled frame IDs) to their slots.
+    slots: HashMap<u64, usize>,
+
+    /// Tags stored in slots.
+    tags: Vec<u64>,
+}
+
+impl Leader {
+    fn new(capacity: usize, bits: u8, bimodal: bool) -> Self {
+        Self {
+            rrip: Rrip::new(capacity, bits),
+            bimodal,
+            bimodal_count: 0,
+            slots: HashMap::with_capacity(capacity),
+            tags: vec![0; capacity],
+        }
+    }
+
+    /// Replays access to the sampled frame, returning `false` on a miss.
+    fn access(&mut self, tag: u64, scan: bool) -> bool {
+        if let Some(&slot) = self.slots.get(&tag) {
+            self.rrip.reference(slot, scan);
+            return true;
+        }
+
+        let slot = if self.slots.len() < self.tags.len() {
+            self.slots.len()
+        } else {
+            let Some(slot) = self.rrip.evict() else {
+                return false;
+            };
+            self.slots.remove(&self.tags[slot]);
+            slot
+        };
+        let rrpv = self
+            .rrip
+            .insertion_rrpv(self.bimodal, &mut self.bimodal_count, scan);
+        self.rrip.insert(slot, rrpv);
+        self.tags[slot] = tag;
+        self.slots.insert(tag, slot);
+        false
+    }
+}
+
+/// Set dueling between SRRIP and BRRIP.
+#[derive(Debug)]
+struct Duel {
+    srrip: Leader,
+
+    brrip: Leader,
+
+    /// Policy selection counter, grows with misses of the SRRIP leader, and
+    /// shrinks with misses of the BRRIP leader.
+    psel: u16,
+}
+
+/// Insertion policy state.
+#[derive(Debug)]
+struct Policy {
+    insertion: RripInsertion,
+
+    /// Number of frames inserted with bimodal insertion.
+    bimodal_count: u32,
+
+    /// Set dueling state, with DRRIP only.
+    ///
+    /// Accesses to sampled frames have to be replayed even on hits, which
+    /// hold a read lock only, so the state is behind a mutex of its own.
+    duel: Option<Mutex<Duel>>,
+}
+
+impl Policy {
+    fn new(config: &RripConfig) -> Self {
+        let duel = (config.insertion == RripInsertion::Dynamic).then(|| {
+            let capacity = (config.capacity / DUEL_SAMPLING as usize).max(1);
+            Mutex::new(Duel {
+                srrip: Leader::new(capacity, config.bits, false),
+                brrip: Leader::new(capacity, config.bits, true),
+                psel: PSEL_MAX / 2,
+            })
+        });
+        Self {
+            insertion: config.insertion,
+            bimodal_count: 0,
+            duel,
+        }
+    }
+
+    /// Replays access to the frame on the leader directories, if the frame is
+    /// sampled.
+    ///
+    /// The tag (frame ID hash) is computed only when set dueling is on.
+    fn observe(&self, tag: impl FnOnce() -> u64, scan: bool) {
+        let Some(duel) = &self.duel else {
+            return;
+        };
+        let tag = tag();
+        if !tag.is_multiple_of(DUEL_SAMPLING) {
+            return;
+        }
+        let mut duel = duel.lock();
+        if !duel.srrip.access(tag, scan) {
+            duel.psel = (duel.psel + 1).min(PSEL_MAX);
+        }
+        if !duel.brrip.access(tag, scan) {
+            duel.psel = duel.psel.saturating_sub(1);
+        }
+    }
+
+    /// Whether new frames should be inserted with bimodal insertion.
+    fn bimodal(&self) -> bool {
+        match self.insertion {
+            RripInsertion::Static => false,
+            RripInsertion::Bimodal => true,
+            // SRRIP leader misses more often, follow BRRIP.
+            RripInsertion::Dynamic => self
+                .duel
+                .as_ref()
+                .is_some_and(|duel| duel.lock().psel > PSEL_MAX / 2),
+        }
+    }
+
+    /// Stores a new frame in the empty slot.
+    fn insert(&mut self, rrip: &mut Rrip, slot: usize, scan: bool) {
+        let rrpv = rrip.insertion_rrpv(self.bimodal(), &mut self.bimodal_count, scan);
+        rrip.insert(slot, rrpv);
+    }
+}
+
+/// Re-Reference Interval Prediction (RRIP) frame replacer.
+///
+/// Every frame has an `M`-bit re-reference prediction value (RRPV), see
+/// [`RripConfig::bits`]. The RRPV of 0 predicts near-immediate re-reference,
+/// while the maximum RRPV predicts re-reference in a distant future. Frames
+/// are evicted in the order of their RRPVs, the one with the highest RRPV
+/// first. When the victim is found, all the frames are aged, so that the
+/// victim's RRPV becomes distant.
+///
+/// Accessing a frame which is already stored resets its RRPV to 0, so it
+/// requires a read lock only. New frames are inserted according to the
+/// insertion policy (see [`RripInsertion`]). Frames accessed as a part of a
+/// scan (see [`AccessType::is_scan`]) are inserted with distant RRPV, and are
+/// not promoted on hits.
+///
+/// Frames are stored in a fixed array of slots, with a single byte of state
+/// per frame. If frame IDs are dense indexes `0..capacity`, consider
+/// [`DenseRripReplacer`], which does not need to map frame IDs to slots.
+pub struct RripReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    rrip: Rrip,
+
+    policy: Policy,
+
+    /// Mapping of frame IDs to their slots.
+    slots: HashMap<F, usize>,
+
+    /// Frame IDs stored in slots.
+    ids: Vec<Option<F>>,
+
+    /// Empty slots.
+    free: Vec<usize>,
+}
+
+impl<F: FrameId> Default for RripReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(RripConfig::default())
+    }
+}
+
+impl<F: FrameId> RripReplacer<F> {
+    /// Creates a new DRRIP replacer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(RripConfig {
+            capacity,
+            ..RripConfig::default()
+        })
+    }
+
+    /// Creates a new RRIP replacer with the given configuration.
+    pub fn with_config(config: RripConfig) -> Self {
+        let capacity = config.capacity;
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                rrip: Rrip::new(capacity, config.bits),
+                policy: Policy::new(&config),
+                slots: HashMap::with_capacity(capacity),
+                ids: vec![None; capacity],
+                // Slots are taken from the back, start with the lowest one.
+                free: (0..capacity).rev().collect(),
+            })),
+        }
+    }
+
+    fn access(&self, id: F, scan: bool) -> EvictResult<(), F> {
+        let tag = || {
+            let mut hasher = DefaultHasher::new();
+            id.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        // Fast path: the frame is already stored, just reset its RRPV.
+        {
+            let inner = self.inner.read();
+            if let Some(&slot) = inner.slots.get(&id) {
+                inner.policy.observe(tag, scan);
+                inner.rrip.reference(slot, scan);
+                return Ok(());
+            }
+        }
+
+        let mut inner = self.inner.write();
+        if let Some(&slot) = inner.slots.get(&id) {
+            inner.policy.observe(tag, scan);
+            inner.rrip.reference(slot, scan);
+            return Ok(());
+        }
+
+        let slot = inner.free.pop().ok_or(EvictError::FrameReplacerFull)?;
+        inner.policy.observe(tag, scan);
+        let Inner { rrip, policy, .. } = &mut *inner;
+        policy.insert(rrip, slot, scan);
+        inner.ids[slot] = Some(id.clone());
+        inner.slots.insert(id, slot);
+
+        Ok(())
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for RripReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let slot = inner.rrip.evict()?;
+        let id = inner.ids[slot].take()?;
+        inner.slots.remove(&id);
+        inner.free.push(slot);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        let inner = self.inner.read();
+        inner.rrip.victim().and_then(|slot| inner.ids[slot].clone())
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        self.access(id, false)
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, access_type: T) -> EvictResult<(), F> {
+        self.access(id, access_type.is_scan())
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let slot = *inner.slots.get(&id).ok_or(EvictError::InvalidFrameId(id))?;
+        inner.rrip.set_state(slot, State::Pinned);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(&slot) = inner.slots.get(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+        inner.rrip.set_state(slot, State::Evictable);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let Some(&slot) = inner.slots.get(&id) else {
+            return Ok(());
+        };
+        if inner.rrip.state(slot) == State::Pinned {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+        inner.rrip.set_state(slot, State::Empty);
+        inner.ids[slot] = None;
+        inner.slots.remove(&id);
+        inner.free.push(slot);
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().rrip.slots.len()
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().rrip.size
+    }
+}
+
+/// RRIP frame replacer for dense frame IDs.
+///
+/// Works exactly as [`RripReplacer`], but frame IDs are expected to be
+/// indexes `0..capacity`, which are used as slots directly. This avoids
+/// hashing frame IDs on every access, and the replacer can never be full.
+pub struct DenseRripReplacer {
+    inner: Arc<RwLock<DenseInner>>,
+}
+
+struct DenseInner {
+    rrip: Rrip,
+
+    policy: Policy,
+}
+
+impl Default for DenseRripReplacer {
+    fn default() -> Self {
+        Self::with_config(RripConfig::default())
+    }
+}
+
+impl DenseRripReplacer {
+    /// Creates a new DRRIP replacer for frame IDs `0..capacity`.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(RripConfig {
+            capacity,
+            ..RripConfig::default()
+        })
+    }
+
+    /// Creates a new RRIP replacer with the given configuration.
+    pub fn with_config(config: RripConfig) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(DenseInner {
+                rrip: Rrip::new(config.capacity, config.bits),
+                policy: Policy::new(&config),
+            })),
+        }
+    }
+
+    fn access(&self, id: usize, scan: bool) -> EvictResult<(), usize> {
+        let tag = || id as u64;
+
+        // Fast path: the frame is already stored, just reset its RRPV.
+        {
+            let inner = self.inner.read();
+            match inner.rrip.slots.get(id) {
+                None => return Err(EvictError::InvalidFrameId(id)),
+                Some(slot) if slot.state != State::Empty => {
+                    inner.policy.observe(tag, scan);
+                    inner.rrip.reference(id, scan);
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+
+        let mut inner = self.inner.write();
+        inner.policy.observe(tag, scan);
+        if inner.rrip.state(id) == State::Empty {
+            let DenseInner { rrip, policy } = &mut *inner;
+            policy.insert(rrip, id, scan);
+        } else {
+            inner.rrip.reference(id, scan);
+        }
+
+        Ok(())
+    }
+}
+
+impl EvictionPolicy<usize> for DenseRripReplacer {
+    type Error = EvictError<usize>;
+
+    fn evict(&self) -> Option<usize> {
+        self.inner.write().rrip.evict()
+    }
+
+    fn peek(&self) -> Option<usize> {
+        self.inner.read().rrip.victim()
+    }
+
+    fn touch(&self, id: usize) -> EvictResult<(), usize> {
+        self.access(id, false)
+    }
+
+    fn touch_with<T: AccessType>(&self, id: usize, access_type: T) -> EvictResult<(), usize> {
+        self.access(id, access_type.is_scan())
+    }
+
+    fn pin(&self, id: usize) -> EvictResult<(), usize> {
+        let mut inner = self.inner.write();
+
+        if inner.rrip.state(id) == State::Empty {
+            return Err(EvictError::InvalidFrameId(id));
+        }
+        inner.rrip.set_state(id, State::Pinned);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: usize) -> EvictResult<(), usize> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        if inner.rrip.state(id) == State::Empty {
+            drop(inner);
+            return self.touch(id);
+        }
+        inner.rrip.set_state(id, State::Evictable);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: usize) -> EvictResult<(), usize> {
+        let mut inner = self.inner.write();
+
+        match inner.rrip.state(id) {
+            State::Empty => {}
+            State::Pinned => return Err(EvictError::PinnedFrameRemoval(id)),
+            State::Evictable => inner.rrip.set_state(id, State::Empty),
+        }
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().rrip.slots.len()
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().rrip.size
+    }
+}
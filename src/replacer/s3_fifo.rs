@@ -0,0 +1,469 @@
+//! S3-FIFO page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the [S3-FIFO paper](https://dl.acm.org/doi/10.1145/3600006.3613147).
+
+use {
+    super::list::{FrameList, QueueView},
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{
+        collections::HashMap,
+        sync::{
+            Arc,
+            atomic::{AtomicU8, Ordering},
+        },
+    },
+};
+
+/// Maximum value of the access frequency counter (it is a 2-bit counter).
+const MAX_FREQ: u8 = 3;
+
+/// Configuration of the S3-FIFO replacer.
+#[derive(Debug)]
+pub struct S3FifoConfig {
+    /// Maximum number of resident frames.
+    pub capacity: usize,
+
+    /// Size of the small (probationary) queue, relative to the capacity.
+    pub small_ratio: f64,
+
+    /// Maximum number of identifiers in the ghost queue, relative to the
+    /// capacity.
+    pub ghost_ratio: f64,
+}
+
+impl Default for S3FifoConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            small_ratio: 0.1,
+            ghost_ratio: 0.9,
+        }
+    }
+}
+
+/// Queue a resident page resides in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Queue {
+    Small,
+    Main,
+}
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    queue: Queue,
+
+    /// Access frequency, saturating at [`MAX_FREQ`].
+    ///
+    /// The counter is atomic, so that it can be updated while holding a read
+    /// lock.
+    freq: AtomicU8,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+/// S3-FIFO frame replacer.
+///
+/// Resident frames are kept in two FIFO queues: the small one `S`, where new
+/// frames are put on probation, and the main one `M`. Identifiers of frames
+/// evicted from `S` are remembered in the ghost queue `G`.
+///
+/// Accessing a resident frame only increments its 2-bit frequency counter,
+/// so it requires a read lock only. Frames are not reordered on access, all
+/// the work is done on eviction:
+///
+/// - While `S` is larger than its target size, frames are taken from its head:
+///   the ones accessed while on probation are moved into `M`, and the first
+///   non-accessed one is evicted (and remembered in `G`). Most of the one-hit
+///   wonders are thus evicted quickly.
+/// - Otherwise, frames are taken from the head of `M`: accessed ones are
+///   reinserted at its tail (with their counter decremented), and the first
+///   non-accessed one is evicted.
+///
+/// New frames are inserted into `S`, unless they are found in `G`, in which
+/// case they go straight into `M`.
+///
+/// Pinned frames stay in their queues, but are skipped on eviction.
+pub struct S3FifoReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: S3FifoConfig,
+
+    /// Target size of the small queue.
+    small_target: usize,
+
+    /// Maximum number of identifiers in the ghost queue.
+    ghost_limit: usize,
+
+    /// Small (probationary) queue.
+    small: FrameList<F>,
+
+    /// Main queue.
+    main: FrameList<F>,
+
+    /// Ghost queue.
+    ghost: FrameList<F>,
+
+    /// Mapping of resident frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    /// Number of evictable pages in the small queue.
+    small_evictable: usize,
+
+    /// Number of evictable pages in the main queue.
+    main_evictable: usize,
+}
+
+impl<F: FrameId> Default for S3FifoReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(S3FifoConfig::default())
+    }
+}
+
+impl<F: FrameId> S3FifoReplacer<F> {
+    /// Creates a new S3-FIFO replacer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(S3FifoConfig {
+            capacity,
+            ..S3FifoConfig::default()
+        })
+    }
+
+    /// Creates a new S3-FIFO replacer with the given configuration.
+    pub fn with_config(config: S3FifoConfig) -> Self {
+        let capacity = config.capacity;
+        let small_target = ((capacity as f64 * config.small_ratio) as usize).max(1);
+        let ghost_limit = (capacity as f64 * config.ghost_ratio) as usize;
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                config,
+                small_target,
+                ghost_limit,
+                small: FrameList::with_capacity(small_target),
+                main: FrameList::with_capacity(capacity),
+                ghost: FrameList::with_capacity(ghost_limit),
+                pages: HashMap::with_capacity(capacity),
+                small_evictable: 0,
+                main_evictable: 0,
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    fn queue(&mut self, queue: Queue) -> &mut FrameList<F> {
+        match queue {
+            Queue::Small => &mut self.small,
+            Queue::Main => &mut self.main,
+        }
+    }
+
+    fn evictable(&mut self, queue: Queue) -> &mut usize {
+        match queue {
+            Queue::Small => &mut self.small_evictable,
+            Queue::Main => &mut self.main_evictable,
+        }
+    }
+
+    /// Moves the page at the head of the queue to its tail.
+    fn rotate(&mut self, queue: Queue) {
+        let queue = self.queue(queue);
+        if let Some(id) = queue.pop_front() {
+            queue.push_back(id);
+        }
+    }
+
+    /// Moves the page from the small queue into the main one.
+    fn promote(&mut self, id: F) {
+        let Some(page) = self.pages.get_mut(&id) else {
+            return;
+        };
+        page.queue = Queue::Main;
+        if page.evictable {
+            self.small_evictable -= 1;
+            self.main_evictable += 1;
+        }
+        self.small.remove(&id);
+        self.main.push_back(id);
+    }
+
+    /// Queue the victim should be taken from.
+    fn target(&self) -> Queue {
+        if (self.small.len() >= self.small_target && self.small_evictable > 0)
+            || self.main_evictable == 0
+        {
+            Queue::Small
+        } else {
+            Queue::Main
+        }
+    }
+
+    /// Moves the pages between the queues until the victim is at the head of
+    /// one of them.
+    fn advance(&mut self) -> Option<(F, Queue)> {
+        if self.small_evictable + self.main_evictable == 0 {
+            return None;
+        }
+
+        loop {
+            let queue = self.target();
+            let id = match queue {
+                Queue::Small => self.small.front(),
+                Queue::Main => self.main.front(),
+            }?
+            .clone();
+            let page = self.pages.get_mut(&id)?;
+            if !page.evictable {
+                self.rotate(queue);
+                continue;
+            }
+
+            let freq = page.freq.get_mut();
+            if *freq == 0 {
+                return Some((id, queue));
+            }
+            match queue {
+                // Accessed while on probation, keep the page.
+                Queue::Small => self.promote(id),
+                // Reinsert accessed page, it has to be accessed again to survive
+                // the next round.
+                Queue::Main => {
+                    *freq -= 1;
+                    self.rotate(queue);
+                }
+            }
+        }
+    }
+
+    /// Removes the resident page.
+    fn detach(&mut self, id: &F) -> Option<PageInfo> {
+        let page = self.pages.remove(id)?;
+        self.queue(page.queue).remove(id);
+        if page.evictable {
+            *self.evictable(page.queue) -= 1;
+        }
+        Some(page)
+    }
+}
+
+/// Simulation of the eviction, which finds the victim without modifying the
+/// replacer.
+struct Simulation<'a, F: FrameId> {
+    inner: &'a Inner<F>,
+    small: QueueView<'a, F>,
+    main: QueueView<'a, F>,
+
+    /// Frequencies changed by the eviction.
+    freqs: HashMap<F, u8>,
+
+    small_evictable: usize,
+    main_evictable: usize,
+}
+
+impl<'a, F: FrameId> Simulation<'a, F> {
+    fn new(inner: &'a Inner<F>) -> Self {
+        Self {
+            inner,
+            small: QueueView::new(&inner.small),
+            main: QueueView::new(&inner.main),
+            freqs: HashMap::new(),
+            small_evictable: inner.small_evictable,
+            main_evictable: inner.main_evictable,
+        }
+    }
+
+    fn queue(&mut self, queue: Queue) -> &mut QueueView<'a, F> {
+        match queue {
+            Queue::Small => &mut self.small,
+            Queue::Main => &mut self.main,
+        }
+    }
+
+    /// Mirrors [`Inner::target`].
+    fn target(&self) -> Queue {
+        if (self.small.len() >= self.inner.small_target && self.small_evictable > 0)
+            || self.main_evictable == 0
+        {
+            Queue::Small
+        } else {
+            Queue::Main
+        }
+    }
+
+    /// Mirrors [`Inner::advance`].
+    fn advance(mut self) -> Option<F> {
+        if self.small_evictable + self.main_evictable == 0 {
+            return None;
+        }
+
+        loop {
+            let queue = self.target();
+            let id = self.queue(queue).front()?.clone();
+            let page = self.inner.pages.get(&id)?;
+            if !page.evictable {
+                let queue = self.queue(queue);
+                queue.pop_front();
+                queue.push_back(id);
+                continue;
+            }
+
+            let freq = self
+                .freqs
+                .get(&id)
+                .copied()
+                .unwrap_or_else(|| page.freq.load(Ordering::Relaxed));
+            if freq == 0 {
+                return Some(id);
+            }
+            match queue {
+                Queue::Small => {
+                    self.small_evictable -= 1;
+                    self.main_evictable += 1;
+                    self.small.pop_front();
+                    self.main.push_back(id);
+                }
+                Queue::Main => {
+                    self.freqs.insert(id.clone(), freq - 1);
+                    self.main.pop_front();
+                    self.main.push_back(id);
+                }
+            }
+        }
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for S3FifoReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let (id, queue) = inner.advance()?;
+        inner.detach(&id);
+
+        // Remember the pages evicted from the small queue.
+        if queue == Queue::Small && inner.ghost_limit > 0 {
+            if inner.ghost.len() >= inner.ghost_limit {
+                inner.ghost.pop_front();
+            }
+            inner.ghost.push_back(id.clone());
+        }
+
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        Simulation::new(&self.inner.read()).advance()
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        // Fast path: the page is resident, just bump its frequency counter.
+        let bump = |page: &PageInfo| {
+            let _ = page
+                .freq
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |freq| {
+                    (freq < MAX_FREQ).then_some(freq + 1)
+                });
+        };
+        {
+            let inner = self.inner.read();
+            if let Some(page) = inner.pages.get(&id) {
+                bump(page);
+                return Ok(());
+            }
+        }
+
+        let mut inner = self.inner.write();
+        if let Some(page) = inner.pages.get(&id) {
+            bump(page);
+            return Ok(());
+        }
+
+        if inner.pages.len() >= inner.config.capacity {
+            return Err(EvictError::FrameReplacerFull);
+        }
+
+        // Pages evicted from the small queue recently go straight into the main one.
+        let queue = if inner.ghost.remove(&id) {
+            Queue::Main
+        } else {
+            Queue::Small
+        };
+        inner.pages.insert(id.clone(), PageInfo {
+            queue,
+            freq: AtomicU8::new(0),
+            evictable: true,
+        });
+        inner.queue(queue).push_back(id);
+        *inner.evictable(queue) += 1;
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // S3-FIFO does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let page = inner
+            .pages
+            .get_mut(&id)
+            .ok_or(EvictError::InvalidFrameId(id))?;
+
+        // No-op if the frame is already in the desired state.
+        if !std::mem::replace(&mut page.evictable, false) {
+            return Ok(());
+        }
+        let queue = page.queue;
+        *inner.evictable(queue) -= 1;
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(page) = inner.pages.get_mut(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+
+        // No-op if the frame is already in the desired state.
+        if std::mem::replace(&mut page.evictable, true) {
+            return Ok(());
+        }
+        let queue = page.queue;
+        *inner.evictable(queue) += 1;
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if inner.pages.get(&id).is_some_and(|page| !page.evictable) {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+
+        // Removed frames are not remembered in the ghost queue.
+        inner.detach(&id);
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        let inner = self.inner.read();
+        inner.small_evictable + inner.main_evictable
+    }
+}
@@ -0,0 +1,87 @@
+//! Set of frame identifiers with random sampling.
+//!
+//! Randomized policies (Random, Hyperbolic etc) pick victims among a few
+//! frames sampled from the set of evictable frames. The [`SampleSet`]
+//! provides O(1) insertion, removal and sampling, by keeping frames in a
+//! vector, and filling the holes left by removed frames with the last one.
+
+use {super::rng::Rng, crate::FrameId, std::collections::HashMap};
+
+/// Unordered set of unique frame identifiers.
+#[derive(Debug)]
+pub(crate) struct SampleSet<F: FrameId> {
+    /// Frames, in no particular order.
+    frames: Vec<F>,
+
+    /// Mapping of frame IDs to their positions in `frames`.
+    index: HashMap<F, usize>,
+}
+
+impl<F: FrameId> SampleSet<F> {
+    /// Creates an empty set with pre-allocated space for `capacity` frames.
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            frames: Vec::with_capacity(capacity),
+            index: HashMap::with_capacity(capacity),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub(crate) fn contains(&self, id: &F) -> bool {
+        self.index.contains_key(id)
+    }
+
+    /// Adds the frame, if it is not in the set yet.
+    pub(crate) fn insert(&mut self, id: F) {
+        if self.contains(&id) {
+            return;
+        }
+        self.index.insert(id.clone(), self.frames.len());
+        self.frames.push(id);
+    }
+
+    /// Removes the frame, returning `false` if it is not found.
+    pub(crate) fn remove(&mut self, id: &F) -> bool {
+        let Some(pos) = self.index.remove(id) else {
+            return false;
+        };
+        self.frames.swap_remove(pos);
+        if let Some(moved) = self.frames.get(pos) {
+            self.index.insert(moved.clone(), pos);
+        }
+        true
+    }
+
+    /// Picks a frame uniformly at random.
+    pub(crate) fn choose(&self, rng: &mut Rng) -> Option<&F> {
+        if self.frames.is_empty() {
+            return None;
+        }
+        self.frames.get(rng.below(self.frames.len()))
+    }
+
+    /// Picks `count` frames at random, or all the frames (in no particular
+    /// order) if there are not more than `count` of them.
+    pub(crate) fn sample<'a>(
+        &'a self,
+        rng: &'a mut Rng,
+        count: usize,
+    ) -> impl Iterator<Item = &'a F> {
+        let exhaustive = self.frames.len() <= count;
+        let mut i = 0;
+        std::iter::from_fn(move || {
+            if i >= count.min(self.frames.len()) {
+                return None;
+            }
+            i += 1;
+            if exhaustive {
+                self.frames.get(i - 1)
+            } else {
+                self.choose(rng)
+            }
+        })
+    }
+}
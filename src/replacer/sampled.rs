@@ -0,0 +1,315 @@
+//! Sampled approximate LRU/LFU page replacement algorithm.
+//!
+//! The algorithm implemented here follows the approximate LRU/LFU eviction of
+//! [Redis](https://redis.io/docs/latest/develop/reference/eviction/).
+
+use {
+    super::{rng::Rng, sample::SampleSet},
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{
+        collections::HashMap,
+        sync::{
+            Arc,
+            atomic::{AtomicU64, Ordering},
+        },
+    },
+};
+
+/// Policy approximated by the sampled replacer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SampledPolicy {
+    /// Least Recently Used: every frame keeps the time of its last access.
+    #[default]
+    Lru,
+
+    /// Least Frequently Used: every frame keeps the number of accesses to it.
+    Lfu,
+}
+
+/// Configuration of the sampled replacer.
+#[derive(Debug)]
+pub struct SampledConfig {
+    /// Maximum number of frames that can be stored in the replacer.
+    pub capacity: usize,
+
+    /// Policy to approximate.
+    pub policy: SampledPolicy,
+
+    /// Number of frames sampled on eviction.
+    ///
+    /// The more frames are sampled, the closer the replacer gets to the exact
+    /// policy, at the expense of slower eviction.
+    pub samples: usize,
+
+    /// Maximum number of eviction candidates, including the victim, kept
+    /// between evictions.
+    ///
+    /// With the pool of 1, victims are picked among the sampled frames only.
+    pub pool_size: usize,
+
+    /// Seed of the pseudo-random generator, used to sample the frames.
+    pub seed: u64,
+}
+
+impl Default for SampledConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            policy: SampledPolicy::Lru,
+            samples: 5,
+            pool_size: 16,
+            seed: 0,
+        }
+    }
+}
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    /// Time of the last access (LRU) or number of accesses (LFU).
+    ///
+    /// The value is atomic, so that it can be updated while holding a read
+    /// lock.
+    stamp: AtomicU64,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+/// Sampled approximate LRU/LFU frame replacer.
+///
+/// Instead of keeping frames ordered, every frame only has a single counter:
+/// the time of its last access, or the number of accesses to it (see
+/// [`SampledConfig::policy`]). Accessing a frame which is already stored only
+/// updates the counter, so it requires a read lock only.
+///
+/// On eviction, a few evictable frames are sampled at random (see
+/// [`SampledConfig::samples`]), and merged into the pool of the best eviction
+/// candidates found so far (see [`SampledConfig::pool_size`]). The worst
+/// frame in the pool (the least recently or the least frequently used one) is
+/// evicted, and the rest of the pool is kept for the next eviction. Frames in
+/// the pool are always ranked by their current counters, so a frame accessed
+/// after it got into the pool is not evicted prematurely.
+///
+/// The pseudo-random generator is seeded explicitly (see
+/// [`SampledConfig::seed`]), so that the same sequence of operations always
+/// results in the same sequence of victims.
+///
+/// Pinned frames are excluded from sampling (and dropped from the pool), but
+/// keep their counters.
+pub struct SampledReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: SampledConfig,
+
+    /// Current time, incremented on every access.
+    ///
+    /// The counter is atomic, so that it can be updated while holding a read
+    /// lock.
+    time: AtomicU64,
+
+    /// Mapping of frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    /// Evictable frames.
+    frames: SampleSet<F>,
+
+    /// Eviction candidates left from the previous evictions.
+    pool: Vec<F>,
+
+    rng: Rng,
+}
+
+impl<F: FrameId> Default for SampledReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(SampledConfig::default())
+    }
+}
+
+impl<F: FrameId> SampledReplacer<F> {
+    /// Creates a new sampled LRU replacer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(SampledConfig {
+            capacity,
+            ..SampledConfig::default()
+        })
+    }
+
+    /// Creates a new sampled replacer with the given configuration.
+    pub fn with_config(config: SampledConfig) -> Self {
+        let capacity = config.capacity;
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                time: AtomicU64::new(0),
+                pages: HashMap::with_capacity(capacity),
+                frames: SampleSet::with_capacity(capacity),
+                pool: Vec::with_capacity(config.pool_size + config.samples),
+                rng: Rng::new(config.seed),
+                config,
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    /// Updates the counter of the page on access.
+    fn bump(&self, page: &PageInfo) {
+        let time = self.time.fetch_add(1, Ordering::Relaxed) + 1;
+        match self.config.policy {
+            SampledPolicy::Lru => page.stamp.store(time, Ordering::Relaxed),
+            SampledPolicy::Lfu => {
+                let _ = page
+                    .stamp
+                    .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |hits| {
+                        hits.checked_add(1)
+                    });
+            }
+        }
+    }
+
+    /// How bad the page is to keep, the larger the better it is to evict.
+    fn idleness(&self, id: &F) -> u64 {
+        let stamp = self
+            .pages
+            .get(id)
+            .map_or(0, |page| page.stamp.load(Ordering::Relaxed));
+        match self.config.policy {
+            SampledPolicy::Lru => self.time.load(Ordering::Relaxed).saturating_sub(stamp),
+            SampledPolicy::Lfu => u64::MAX - stamp,
+        }
+    }
+
+    /// Merges sampled frames into the pool of candidates, and returns the
+    /// candidates ordered from the best to the worst frame to evict.
+    fn candidates(&self, rng: &mut Rng) -> Vec<F> {
+        let mut pool: Vec<(u64, &F)> = self
+            .pool
+            .iter()
+            .filter(|id| self.frames.contains(id))
+            .map(|id| (self.idleness(id), id))
+            .collect();
+        for id in self.frames.sample(rng, self.config.samples.max(1)) {
+            if !pool.iter().any(|(_, candidate)| *candidate == id) {
+                pool.push((self.idleness(id), id));
+            }
+        }
+
+        // Stable sort, so that ties are broken in favour of older candidates.
+        pool.sort_by_key(|(idleness, _)| std::cmp::Reverse(*idleness));
+        pool.truncate(self.config.pool_size.max(1));
+        pool.into_iter().map(|(_, id)| id.clone()).collect()
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for SampledReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let mut rng = inner.rng.clone();
+        let mut pool = inner.candidates(&mut rng);
+        inner.rng = rng;
+        if pool.is_empty() {
+            inner.pool.clear();
+            return None;
+        }
+        let id = pool.remove(0);
+        inner.pool = pool;
+        inner.frames.remove(&id);
+        inner.pages.remove(&id);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        let inner = self.inner.read();
+        // Use a copy of the generator, so that the next eviction picks the same frame.
+        inner.candidates(&mut inner.rng.clone()).into_iter().next()
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        // Fast path: the frame is already stored, just update its counter.
+        {
+            let inner = self.inner.read();
+            if let Some(page) = inner.pages.get(&id) {
+                inner.bump(page);
+                return Ok(());
+            }
+        }
+
+        let mut inner = self.inner.write();
+        if let Some(page) = inner.pages.get(&id) {
+            inner.bump(page);
+            return Ok(());
+        }
+
+        if inner.pages.len() >= inner.config.capacity {
+            return Err(EvictError::FrameReplacerFull);
+        }
+        let page = PageInfo {
+            stamp: AtomicU64::new(0),
+            evictable: true,
+        };
+        inner.bump(&page);
+        inner.pages.insert(id.clone(), page);
+        inner.frames.insert(id);
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // Sampled replacer does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let page = inner
+            .pages
+            .get_mut(&id)
+            .ok_or(EvictError::InvalidFrameId(id.clone()))?;
+        page.evictable = false;
+        inner.frames.remove(&id);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(page) = inner.pages.get_mut(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+        page.evictable = true;
+        inner.frames.insert(id);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if let Some(page) = inner.pages.get(&id) {
+            if !page.evictable {
+                return Err(EvictError::PinnedFrameRemoval(id));
+            }
+            inner.pages.remove(&id);
+            inner.frames.remove(&id);
+        }
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().frames.len()
+    }
+}
@@ -0,0 +1,251 @@
+//! SIEVE page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the [SIEVE paper](https://www.usenix.org/conference/nsdi24/presentation/zhang-yazhuo).
+
+use {
+    super::list::FrameList,
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{
+        collections::HashMap,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+    },
+};
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    /// Visited bit, set on every access.
+    ///
+    /// The bit is atomic, so that it can be set while holding a read lock.
+    visited: AtomicBool,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+/// SIEVE frame replacer.
+///
+/// Frames are kept in a FIFO queue, each with a visited bit, which is set on
+/// every access. When a victim is needed, the hand moves from the oldest frame
+/// towards the newest one: visited frames have their bits cleared, but retain
+/// their positions in the queue, and the first non-visited frame is evicted.
+/// The hand stays where the victim was, and wraps around to the oldest frame
+/// once it reaches the newest one.
+///
+/// Unlike Clock, new frames are always inserted at the head of the queue, so
+/// frames that survived a pass of the hand stay behind it, while new frames
+/// (which are mostly one-hit wonders) are quickly examined and evicted.
+///
+/// Accessing a resident frame only sets its visited bit, so it requires a
+/// read lock only.
+///
+/// Pinned frames stay in the queue, but are skipped by the hand.
+pub struct SieveReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Maximum number of frames that can be stored in the replacer.
+    capacity: usize,
+
+    /// Number of evictable frames in the replacer.
+    size: usize,
+
+    /// Mapping of frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    /// Queue of frames, the oldest frame is at the front.
+    queue: FrameList<F>,
+
+    /// Frame the hand points to, `None` if it points to the oldest frame.
+    hand: Option<F>,
+}
+
+impl<F: FrameId> SieveReplacer<F> {
+    /// Creates a new SIEVE replacer.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                capacity,
+                size: 0,
+                pages: HashMap::with_capacity(capacity),
+                queue: FrameList::with_capacity(capacity),
+                hand: None,
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    /// Frame the hand points to.
+    fn hand(&self) -> Option<&F> {
+        self.hand.as_ref().or_else(|| self.queue.front())
+    }
+
+    /// The frame following `id`, wrapping around to the oldest frame.
+    fn next(&self, id: &F) -> Option<&F> {
+        self.queue.next(id).or_else(|| self.queue.front())
+    }
+
+    /// Finds the frame the hand stops at, without moving the hand.
+    ///
+    /// The hand stops at the first evictable non-visited frame. If all
+    /// evictable frames are visited, the hand clears their bits during the
+    /// first pass, and stops at the first evictable frame.
+    fn victim(&self) -> Option<F> {
+        if self.size == 0 {
+            return None;
+        }
+        let start = self.hand()?;
+        let evictable = std::iter::successors(Some(start), |id| {
+            self.next(id).filter(|next| *next != start)
+        })
+        .filter_map(|id| Some((id, self.pages.get(id)?)))
+        .filter(|(_, page)| page.evictable);
+
+        let mut first = None;
+        for (id, page) in evictable {
+            if !page.visited.load(Ordering::Relaxed) {
+                return Some(id.clone());
+            }
+            first.get_or_insert(id);
+        }
+        first.cloned()
+    }
+
+    /// Moves the hand, clearing visited bits, until a victim is found.
+    fn advance(&mut self) -> Option<F> {
+        if self.size == 0 {
+            return None;
+        }
+        loop {
+            let id = self.hand()?.clone();
+            let page = self.pages.get_mut(&id)?;
+            if page.evictable && !std::mem::take(page.visited.get_mut()) {
+                return Some(id);
+            }
+            self.hand = self.next(&id).cloned();
+        }
+    }
+
+    /// Removes the frame from the queue, moving the hand if it points to it.
+    fn unlink(&mut self, id: &F) {
+        if self.hand.as_ref() == Some(id) {
+            self.hand = self.queue.next(id).cloned();
+        }
+        self.queue.remove(id);
+        self.pages.remove(id);
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for SieveReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let id = inner.advance()?;
+        inner.unlink(&id);
+        inner.size -= 1;
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        self.inner.read().victim()
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        // Fast path: the frame is already stored, just set its visited bit.
+        {
+            let inner = self.inner.read();
+            if let Some(page) = inner.pages.get(&id) {
+                page.visited.store(true, Ordering::Relaxed);
+                return Ok(());
+            }
+        }
+
+        let mut inner = self.inner.write();
+        if let Some(page) = inner.pages.get(&id) {
+            page.visited.store(true, Ordering::Relaxed);
+            return Ok(());
+        }
+
+        if inner.pages.len() >= inner.capacity {
+            return Err(EvictError::FrameReplacerFull);
+        }
+        inner.pages.insert(id.clone(), PageInfo {
+            visited: AtomicBool::new(false),
+            evictable: true,
+        });
+        inner.queue.push_back(id);
+        inner.size += 1;
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // SIEVE does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let page = inner
+            .pages
+            .get_mut(&id)
+            .ok_or(EvictError::InvalidFrameId(id))?;
+
+        // No-op if the frame is already in the desired state.
+        if !std::mem::replace(&mut page.evictable, false) {
+            return Ok(());
+        }
+        inner.size -= 1;
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(page) = inner.pages.get_mut(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+
+        // No-op if the frame is already in the desired state.
+        if std::mem::replace(&mut page.evictable, true) {
+            return Ok(());
+        }
+        inner.size += 1;
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let Some(page) = inner.pages.get(&id) else {
+            return Ok(());
+        };
+        if !page.evictable {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+        inner.unlink(&id);
+        inner.size -= 1;
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().size
+    }
+}
@@ -0,0 +1,255 @@
+//! Segmented LRU page replacement algorithm.
+
+use {
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    hlc_gen::{HlcGenerator, HlcTimestamp},
+    parking_lot::RwLock,
+    priority_queue::PriorityQueue,
+    std::{
+        cmp::Reverse,
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    },
+};
+
+/// Configuration of the SLRU replacer.
+#[derive(Debug)]
+pub struct SlruConfig {
+    /// Maximum number of frames to keep track of.
+    pub capacity: usize,
+
+    /// Size ratios of segments, relative to the capacity.
+    ///
+    /// The first segment is the probationary one, the rest are protected
+    /// segments, in the order of increasing protection level. Protected
+    /// segments are bounded by their ratios, while the probationary segment
+    /// takes whatever capacity is left.
+    ///
+    /// With a single (or no) segment, the replacer works as a plain LRU.
+    pub segments: Vec<f64>,
+}
+
+impl Default for SlruConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            segments: vec![0.2, 0.8],
+        }
+    }
+}
+
+/// Segmented LRU (SLRU) frame replacer.
+///
+/// Frames are split into a probationary segment and one or more protected
+/// segments, each of which is an LRU list ordered the same way as in
+/// [`LruReplacer`](crate::LruReplacer). New frames are put into the
+/// probationary segment, and every subsequent access promotes the frame into
+/// the next segment up. When a protected segment overflows, its least recently
+/// used frame is demoted into the segment below.
+///
+/// Victims are taken from the lowest non-empty segment, so frames accessed only
+/// once are evicted before frames which proved to be useful.
+///
+/// Pinned frames are taken out of their segments, and are put back into the
+/// most recently used position of the same segment once unpinned.
+pub struct SlruReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Maximum number of frames that can be stored in the replacer.
+    capacity: usize,
+
+    /// Maximum number of frames in each of the segments.
+    limits: Vec<usize>,
+
+    /// Evictable frames, by segment.
+    segments: Vec<PriorityQueue<F, Reverse<HlcTimestamp>>>,
+
+    /// Segments of all the frames in the replacer, including pinned ones.
+    location: HashMap<F, usize>,
+
+    /// Frames which are not candidates for eviction.
+    pinned: HashSet<F>,
+
+    /// Monotonically increasing sequence of timestamps.
+    /// Used to determine the order and time of page accesses.
+    seq: HlcGenerator,
+}
+
+impl<F: FrameId> Default for SlruReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(SlruConfig::default())
+    }
+}
+
+impl<F: FrameId> SlruReplacer<F> {
+    /// Creates a new SLRU replacer with a probationary and a single protected
+    /// segment.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(SlruConfig {
+            capacity,
+            ..SlruConfig::default()
+        })
+    }
+
+    /// Creates a new SLRU replacer with the given configuration.
+    pub fn with_config(config: SlruConfig) -> Self {
+        let capacity = config.capacity;
+        let mut limits = config
+            .segments
+            .iter()
+            .enumerate()
+            .map(|(i, ratio)| match i {
+                0 => capacity,
+                _ => ((capacity as f64 * ratio) as usize).max(1),
+            })
+            .collect::<Vec<_>>();
+        if limits.is_empty() {
+            limits.push(capacity);
+        }
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                capacity,
+                segments: limits.iter().map(|_| PriorityQueue::new()).collect(),
+                limits,
+                location: HashMap::with_capacity(capacity),
+                pinned: HashSet::new(),
+                seq: HlcGenerator::default(),
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    /// Pushes the frame into the MRU position of the segment.
+    ///
+    /// If the segment overflows, the least recently used frames are demoted
+    /// into the lower segments.
+    fn push(&mut self, id: F, segment: usize) -> EvictResult<(), F> {
+        let priority = self
+            .seq
+            .next_timestamp()
+            .ok_or(EvictError::SequenceExhausted)?;
+        self.segments[segment].push(id.clone(), Reverse(priority));
+        self.location.insert(id, segment);
+
+        for segment in (1..=segment).rev() {
+            if self.segments[segment].len() <= self.limits[segment] {
+                break;
+            }
+            if let Some((demoted, _)) = self.segments[segment].pop() {
+                let priority = self
+                    .seq
+                    .next_timestamp()
+                    .ok_or(EvictError::SequenceExhausted)?;
+                self.segments[segment - 1].push(demoted.clone(), Reverse(priority));
+                self.location.insert(demoted, segment - 1);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for SlruReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let (id, _) = inner
+            .segments
+            .iter_mut()
+            .find_map(|segment| segment.pop())?;
+        inner.location.remove(&id);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        let inner = self.inner.read();
+        inner
+            .segments
+            .iter()
+            .find_map(|segment| segment.peek())
+            .map(|(id, _)| id.clone())
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let Some(&segment) = inner.location.get(&id) else {
+            // Ensure that we are not beyond the capacity.
+            if inner.location.len() >= inner.capacity {
+                return Err(EvictError::FrameReplacerFull);
+            }
+            return inner.push(id, 0);
+        };
+
+        // Promote the frame into the next segment up.
+        let next = (segment + 1).min(inner.segments.len() - 1);
+        if inner.pinned.contains(&id) {
+            inner.location.insert(id, next);
+            return Ok(());
+        }
+        inner.segments[segment].remove(&id);
+        inner.push(id, next)
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // No special handling for access type in SLRU.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let segment = *inner
+            .location
+            .get(&id)
+            .ok_or(EvictError::InvalidFrameId(id.clone()))?;
+        inner.segments[segment].remove(&id);
+        inner.pinned.insert(id);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(&segment) = inner.location.get(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+
+        if inner.pinned.remove(&id) {
+            inner.push(id, segment)?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if inner.pinned.contains(&id) {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+        if let Some(segment) = inner.location.remove(&id) {
+            inner.segments[segment].remove(&id);
+        }
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner
+            .read()
+            .segments
+            .iter()
+            .map(PriorityQueue::len)
+            .sum()
+    }
+}
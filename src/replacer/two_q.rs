@@ -0,0 +1,253 @@
+//! 2Q page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the [2Q paper](https://www.vldb.org/conf/1994/P439.PDF).
+
+use {
+    super::list::FrameList,
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::RwLock,
+    std::{collections::HashSet, sync::Arc},
+};
+
+/// Configuration of the 2Q replacer.
+#[derive(Debug)]
+pub struct TwoQConfig {
+    /// Maximum number of resident frames to keep track of.
+    pub capacity: usize,
+
+    /// Share of the capacity used by the `A1in` FIFO queue (`Kin` in the
+    /// paper).
+    ///
+    /// While `A1in` holds more frames than this threshold, victims are taken
+    /// from it, otherwise they are taken from `Am`.
+    pub kin_ratio: f64,
+
+    /// Size of the `A1out` ghost queue, relative to the capacity (`Kout` in
+    /// the paper).
+    pub kout_ratio: f64,
+}
+
+impl Default for TwoQConfig {
+    fn default() -> Self {
+        // Values recommended by the paper.
+        Self {
+            capacity: 4096,
+            kin_ratio: 0.25,
+            kout_ratio: 0.5,
+        }
+    }
+}
+
+/// Implements the full version of the 2Q page replacement algorithm.
+///
+/// Frames referenced for the first time are put into the `A1in` FIFO queue.
+/// Re-references of frames in `A1in` are considered correlated, and do not
+/// change the frames' positions. Identifiers of frames evicted from `A1in` are
+/// remembered in the `A1out` ghost queue, and if such a frame is referenced
+/// again, it is considered hot and is put into the `Am` LRU list.
+///
+/// Since frames referenced only once never make it into `Am`, sequential
+/// scans do not flush hot frames out of the replacer.
+pub struct TwoQReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: TwoQConfig,
+
+    /// Threshold of the `A1in` queue size.
+    kin: usize,
+
+    /// Maximum size of the `A1out` queue.
+    kout: usize,
+
+    /// FIFO queue of frames referenced once.
+    a1in: FrameList<F>,
+
+    /// Ghost FIFO queue of frames evicted from `A1in`.
+    a1out: FrameList<F>,
+
+    /// LRU list of hot frames.
+    am: FrameList<F>,
+
+    /// Resident frames which are not candidates for eviction.
+    pinned: HashSet<F>,
+}
+
+/// Resident queue the victim is taken from.
+#[derive(Clone, Copy)]
+enum Queue {
+    A1in,
+    Am,
+}
+
+impl<F: FrameId> Default for TwoQReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(TwoQConfig::default())
+    }
+}
+
+impl<F: FrameId> TwoQReplacer<F> {
+    /// Creates a new 2Q replacer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(TwoQConfig {
+            capacity,
+            ..TwoQConfig::default()
+        })
+    }
+
+    /// Creates a new 2Q replacer with the given configuration.
+    pub fn with_config(config: TwoQConfig) -> Self {
+        let capacity = config.capacity;
+        let kin = ((capacity as f64 * config.kin_ratio) as usize).max(1);
+        let kout = ((capacity as f64 * config.kout_ratio) as usize).max(1);
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                config,
+                kin,
+                kout,
+                a1in: FrameList::with_capacity(capacity),
+                a1out: FrameList::with_capacity(kout),
+                am: FrameList::with_capacity(capacity),
+                pinned: HashSet::new(),
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    fn is_resident(&self, id: &F) -> bool {
+        self.a1in.contains(id) || self.am.contains(id)
+    }
+
+    fn resident(&self) -> usize {
+        self.a1in.len() + self.am.len()
+    }
+
+    /// Finds the next victim along with the queue it resides in.
+    fn victim(&self) -> Option<(F, Queue)> {
+        let first_evictable =
+            |list: &FrameList<F>| list.iter().find(|id| !self.pinned.contains(*id)).cloned();
+        let a1in = first_evictable(&self.a1in);
+        let am = first_evictable(&self.am);
+
+        // Evict from `A1in` if it exceeds its threshold, or if there is nothing
+        // else to evict.
+        match (a1in, am) {
+            (Some(id), None) => Some((id, Queue::A1in)),
+            (Some(id), Some(_)) if self.a1in.len() > self.kin => Some((id, Queue::A1in)),
+            (_, Some(id)) => Some((id, Queue::Am)),
+            (None, None) => None,
+        }
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for TwoQReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let (id, queue) = inner.victim()?;
+
+        match queue {
+            Queue::A1in => {
+                // Remember the identifier of the victim in the ghost queue.
+                inner.a1in.remove(&id);
+                inner.a1out.push_back(id.clone());
+                if inner.a1out.len() > inner.kout {
+                    inner.a1out.pop_front();
+                }
+            }
+            Queue::Am => {
+                inner.am.remove(&id);
+            }
+        }
+
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        self.inner.read().victim().map(|(id, _)| id)
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Hot frame is moved to the MRU position of `Am`.
+        if inner.am.contains(&id) {
+            inner.am.push_back(id);
+            return Ok(());
+        }
+
+        // Correlated reference, nothing to do.
+        if inner.a1in.contains(&id) {
+            return Ok(());
+        }
+
+        if inner.resident() >= inner.config.capacity {
+            return Err(EvictError::FrameReplacerFull);
+        }
+
+        // Frame referenced again after being evicted from `A1in` is a hot one.
+        if inner.a1out.remove(&id) {
+            inner.am.push_back(id);
+        } else {
+            inner.a1in.push_back(id);
+        }
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // 2Q does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if !inner.is_resident(&id) {
+            return Err(EvictError::InvalidFrameId(id));
+        }
+        inner.pinned.insert(id);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        if !inner.is_resident(&id) {
+            drop(inner);
+            return self.touch(id);
+        }
+        inner.pinned.remove(&id);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        if inner.pinned.contains(&id) {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+
+        // Removed frames are not remembered in the ghost queue.
+        if !inner.a1in.remove(&id) {
+            inner.am.remove(&id);
+        }
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        let inner = self.inner.read();
+        inner.resident() - inner.pinned.len()
+    }
+}
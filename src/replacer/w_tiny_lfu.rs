@@ -0,0 +1,837 @@
+//! W-TinyLFU page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the [TinyLFU paper](https://dl.acm.org/doi/10.1145/3149371),
+//! with the window size adaptation following the [Caffeine](https://github.com/ben-manes/caffeine)
+//! hill climber.
+
+use {
+    super::list::FrameList,
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    parking_lot::{Mutex, RwLock, RwLockWriteGuard},
+    std::{
+        collections::HashMap,
+        hash::{DefaultHasher, Hash, Hasher},
+        sync::Arc,
+    },
+};
+
+/// Number of rows (hash functions) in the frequency sketch.
+const SKETCH_DEPTH: usize = 4;
+
+/// Maximum value of a frequency sketch counter (it is a 4-bit counter).
+const SKETCH_MAX_COUNT: u8 = 15;
+
+/// Seeds of the frequency sketch hash functions, one per row.
+const SKETCH_SEEDS: [u64; SKETCH_DEPTH] = [
+    0xc3a5_c85c_97cb_3127,
+    0xb492_b66f_be98_f273,
+    0x9ae1_6a3b_2f90_404f,
+    0xcbf2_9ce4_8422_2325,
+];
+
+/// Maximum number of accesses to resident frames, which are recorded while
+/// holding a read lock, before they are applied.
+const MAX_PENDING_ACCESSES: usize = 64;
+
+/// Initial window size adjustment of the hill climber, relative to the
+/// capacity.
+const HILL_CLIMBER_STEP: f64 = 0.0625;
+
+/// Decay rate of the hill climber step.
+const HILL_CLIMBER_STEP_DECAY: f64 = 0.98;
+
+/// Change of the hit rate that makes the hill climber restart with the initial
+/// step.
+const HILL_CLIMBER_RESTART_THRESHOLD: f64 = 0.05;
+
+/// Configuration of the W-TinyLFU replacer.
+#[derive(Debug)]
+pub struct WTinyLfuConfig {
+    /// Maximum number of frames to keep track of.
+    pub capacity: usize,
+
+    /// Size of the window region, relative to the capacity.
+    ///
+    /// If hill climbing is enabled, this is the initial size of the window.
+    pub window_ratio: f64,
+
+    /// Size of the protected segment, relative to the size of the main region.
+    pub protected_ratio: f64,
+
+    /// Whether to adapt the size of the window region to the workload.
+    ///
+    /// The hit rate is sampled periodically, and the window is grown or
+    /// shrunk in the direction which improved the hit rate during the
+    /// previous sample.
+    pub hill_climbing: bool,
+}
+
+impl Default for WTinyLfuConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            window_ratio: 0.01,
+            protected_ratio: 0.8,
+            hill_climbing: false,
+        }
+    }
+}
+
+/// Count-Min sketch of access frequencies, with 4-bit counters.
+///
+/// Once the number of recorded accesses reaches the sample size, all the
+/// counters are halved, so that the sketch reflects recent history only.
+#[derive(Debug)]
+struct FrequencySketch {
+    /// Counters, `SKETCH_DEPTH` rows of `mask + 1` counters each.
+    table: Vec<u8>,
+
+    /// Mask to map hashes to the counters within a row.
+    mask: usize,
+
+    /// Number of accesses recorded since the last reset.
+    additions: usize,
+
+    /// Number of accesses after which the counters are halved.
+    sample_size: usize,
+}
+
+impl FrequencySketch {
+    fn new(capacity: usize) -> Self {
+        // Few counters per frame keep the error due to hash collisions low.
+        let width = (4 * capacity).max(64).next_power_of_two();
+        Self {
+            table: vec![0; SKETCH_DEPTH * width],
+            mask: width - 1,
+            additions: 0,
+            sample_size: 10 * capacity.max(1),
+        }
+    }
+
+    /// Positions of the frame's counters, one per row.
+    fn slots<F: Hash>(&self, id: &F) -> [usize; SKETCH_DEPTH] {
+        let mut hasher = DefaultHasher::new();
+        id.hash(&mut hasher);
+        let hash = hasher.finish();
+        std::array::from_fn(|row| {
+            let mixed = (hash ^ SKETCH_SEEDS[row]).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+            row * (self.mask + 1) + ((mixed >> 32) as usize & self.mask)
+        })
+    }
+
+    /// Estimated access frequency of the frame.
+    fn frequency<F: Hash>(&self, id: &F) -> u8 {
+        self.slots(id)
+            .into_iter()
+            .map(|slot| self.table[slot])
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Records an access to the frame.
+    fn increment<F: Hash>(&mut self, id: &F) {
+        for slot in self.slots(id) {
+            let count = &mut self.table[slot];
+            *count = (*count + 1).min(SKETCH_MAX_COUNT);
+        }
+
+        self.additions += 1;
+        if self.additions >= self.sample_size {
+            for count in &mut self.table {
+                *count >>= 1;
+            }
+            self.additions /= 2;
+        }
+    }
+
+    /// Estimated access frequency of the frame, as it would be after recording
+    /// the given accesses.
+    fn frequency_after<F: Hash>(&self, id: &F, accesses: &[F]) -> u8 {
+        let slots = self.slots(id);
+        let mut counts = slots.map(|slot| self.table[slot]);
+        let mut additions = self.additions;
+        for access in accesses {
+            for slot in self.slots(access) {
+                if let Some(row) = slots.iter().position(|own| *own == slot) {
+                    counts[row] = (counts[row] + 1).min(SKETCH_MAX_COUNT);
+                }
+            }
+
+            additions += 1;
+            if additions >= self.sample_size {
+                for count in &mut counts {
+                    *count >>= 1;
+                }
+                additions /= 2;
+            }
+        }
+        counts.into_iter().min().unwrap_or_default()
+    }
+}
+
+/// Adapts the size of the window region to the workload.
+#[derive(Debug, Clone)]
+struct HillClimber {
+    /// Number of hits in the current sample.
+    hits: usize,
+
+    /// Number of misses in the current sample.
+    misses: usize,
+
+    /// Number of accesses in a sample.
+    sample_size: usize,
+
+    /// Hit rate of the previous sample.
+    prev_hit_rate: f64,
+
+    /// Signed window size adjustment (in frames) of the next step.
+    step: f64,
+}
+
+impl HillClimber {
+    fn new(capacity: usize) -> Self {
+        Self {
+            hits: 0,
+            misses: 0,
+            sample_size: 10 * capacity.max(1),
+            prev_hit_rate: 0.0,
+            step: HILL_CLIMBER_STEP * capacity as f64,
+        }
+    }
+
+    /// Records an access, returning the window size adjustment once the
+    /// sample is complete.
+    fn record(&mut self, hit: bool, capacity: usize) -> Option<f64> {
+        if hit {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        let total = self.hits + self.misses;
+        if total < self.sample_size {
+            return None;
+        }
+
+        let hit_rate = self.hits as f64 / total as f64;
+        let change = hit_rate - self.prev_hit_rate;
+        // Keep going in the same direction if the hit rate has improved, and turn
+        // around otherwise.
+        let direction = if change >= 0.0 { 1.0 } else { -1.0 };
+        let step = self.step * direction;
+        self.step = if change.abs() >= HILL_CLIMBER_RESTART_THRESHOLD {
+            HILL_CLIMBER_STEP * capacity as f64 * step.signum()
+        } else {
+            step * HILL_CLIMBER_STEP_DECAY
+        };
+        self.prev_hit_rate = hit_rate;
+        self.hits = 0;
+        self.misses = 0;
+
+        Some(step)
+    }
+}
+
+/// Region a page resides in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Region {
+    Window,
+    Probation,
+    Protected,
+}
+
+/// Page information.
+#[derive(Debug)]
+struct PageInfo {
+    region: Region,
+
+    /// Whether the page is pinned or should not be considered for eviction.
+    evictable: bool,
+}
+
+/// Window TinyLFU (W-TinyLFU) frame replacer.
+///
+/// New frames are put into a small window LRU region, which absorbs bursts of
+/// accesses. The rest of the capacity is given to the main region, which is
+/// an SLRU with probationary and protected segments (see
+/// [`SlruReplacer`](crate::SlruReplacer)).
+///
+/// Access frequencies of all the frames (including the ones evicted long ago)
+/// are approximated by a TinyLFU Count-Min sketch, which is aged by halving
+/// its counters periodically. When the window overflows, its least recently
+/// used frame becomes a candidate for the main region: it is admitted only if
+/// its frequency is higher than the frequency of the main region's victim,
+/// and is evicted otherwise.
+///
+/// The size of the window can optionally be adapted to the workload (see
+/// [`WTinyLfuConfig::hill_climbing`]).
+///
+/// Window frames are moved into the main region for free while it has room.
+///
+/// Accessing a resident frame only requires a read lock: the access is
+/// recorded in a small buffer, and the buffered accesses are applied in order
+/// before any operation which takes the write lock (or once the buffer is
+/// full). Peeking only requires a read lock as well: the buffered accesses are
+/// taken into account without being applied.
+///
+/// Pinned frames stay in their regions, but are never picked as victims.
+pub struct WTinyLfuReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: WTinyLfuConfig,
+
+    /// Target size of the window region.
+    window_target: usize,
+
+    /// Number of evictable frames in the replacer.
+    size: usize,
+
+    /// Window LRU region.
+    window: FrameList<F>,
+
+    /// Probationary segment of the main region.
+    probation: FrameList<F>,
+
+    /// Protected segment of the main region.
+    protected: FrameList<F>,
+
+    /// Mapping of frame IDs to contained page information.
+    pages: HashMap<F, PageInfo>,
+
+    sketch: FrequencySketch,
+
+    /// Window size adaptation, if enabled.
+    climber: Option<HillClimber>,
+
+    /// Accesses to resident pages, which are not applied yet.
+    pending: Mutex<Vec<F>>,
+}
+
+impl<F: FrameId> Default for WTinyLfuReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(WTinyLfuConfig::default())
+    }
+}
+
+impl<F: FrameId> WTinyLfuReplacer<F> {
+    /// Creates a new W-TinyLFU replacer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_config(WTinyLfuConfig {
+            capacity,
+            ..WTinyLfuConfig::default()
+        })
+    }
+
+    /// Creates a new W-TinyLFU replacer with the given configuration.
+    pub fn with_config(config: WTinyLfuConfig) -> Self {
+        let capacity = config.capacity;
+        let window_target =
+            ((capacity as f64 * config.window_ratio) as usize).clamp(1, capacity.max(1));
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                window_target,
+                size: 0,
+                window: FrameList::with_capacity(window_target),
+                probation: FrameList::with_capacity(capacity),
+                protected: FrameList::with_capacity(capacity),
+                pages: HashMap::with_capacity(capacity),
+                sketch: FrequencySketch::new(capacity),
+                climber: config.hill_climbing.then(|| HillClimber::new(capacity)),
+                pending: Mutex::new(Vec::with_capacity(MAX_PENDING_ACCESSES)),
+                config,
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> WTinyLfuReplacer<F> {
+    /// Takes the write lock, applying the pending accesses.
+    fn write(&self) -> RwLockWriteGuard<'_, Inner<F>> {
+        let mut inner = self.inner.write();
+        for id in std::mem::take(inner.pending.get_mut()) {
+            inner.access(id);
+        }
+        inner
+    }
+}
+
+/// Regions of the replacer, along with the state deciding where pages go.
+///
+/// Implemented by [`Inner`] itself, and by [`Simulation`], which applies the
+/// pending accesses without modifying the replacer, so that `peek` finds the
+/// same victim as `evict` while holding a read lock only.
+trait Regions<F: FrameId> {
+    fn config(&self) -> &WTinyLfuConfig;
+
+    /// Number of evictable frames.
+    fn size(&self) -> usize;
+
+    /// Target size of the window region.
+    fn window_target(&self) -> usize;
+
+    fn set_window_target(&mut self, target: usize);
+
+    fn climber(&mut self) -> Option<&mut HillClimber>;
+
+    /// Region the page resides in, if it is resident.
+    fn region_of(&self, id: &F) -> Option<Region>;
+
+    fn is_evictable(&self, id: &F) -> bool;
+
+    /// Number of pages in the region.
+    fn len(&self, region: Region) -> usize;
+
+    /// Pages of the region, from the least recently used one.
+    fn iter(&self, region: Region) -> impl Iterator<Item = F> + '_;
+
+    /// Moves the page into the MRU position of the region.
+    fn move_to(&mut self, id: F, region: Region);
+
+    /// Estimated access frequency of the frame.
+    fn frequency(&self, id: &F) -> u8;
+
+    /// Records an access to the frame in the frequency sketch.
+    fn increment(&mut self, id: &F);
+
+    fn main_capacity(&self) -> usize {
+        self.config().capacity.saturating_sub(self.window_target())
+    }
+
+    fn protected_limit(&self) -> usize {
+        (self.main_capacity() as f64 * self.config().protected_ratio) as usize
+    }
+
+    /// Demotes the least recently used protected pages into the probationary
+    /// segment, until the protected segment is within its limit.
+    fn balance_protected(&mut self) {
+        while self.len(Region::Protected) > self.protected_limit() {
+            let Some(id) = self.iter(Region::Protected).next() else {
+                break;
+            };
+            self.move_to(id, Region::Probation);
+        }
+    }
+
+    /// The first evictable page, in the given order.
+    fn first_evictable(&self, mut ids: impl Iterator<Item = F>) -> Option<F> {
+        ids.find(|id| self.is_evictable(id))
+    }
+
+    /// Number of the least recently used window pages to be moved into the
+    /// main region by [`migrate`](Inner::migrate).
+    fn pending_migrations(&self) -> usize {
+        let main = self.len(Region::Probation) + self.len(Region::Protected);
+        self.len(Region::Window)
+            .saturating_sub(self.window_target())
+            .min(self.main_capacity().saturating_sub(main))
+    }
+
+    /// Finds the next victim, along with the window candidate to be admitted
+    /// into the main region in its place.
+    ///
+    /// Window pages to be moved into the main region are treated as if they
+    /// had been moved already.
+    fn victim(&self) -> Option<(F, Option<F>)> {
+        if self.size() == 0 {
+            return None;
+        }
+        let migrated = self.pending_migrations();
+        let window = || self.iter(Region::Window).skip(migrated);
+
+        // Once the window is full, its least recently used page is the one to leave
+        // it next, so it competes with the main region's victim.
+        let candidate = if self.len(Region::Window) - migrated >= self.window_target() {
+            self.first_evictable(window())
+        } else {
+            None
+        };
+        let probation = self
+            .iter(Region::Probation)
+            .chain(self.iter(Region::Window).take(migrated));
+        let victim = self.first_evictable(probation.chain(self.iter(Region::Protected)));
+
+        match (candidate, victim) {
+            (Some(candidate), Some(victim)) => {
+                // TinyLFU admission: keep the more frequently used of the two.
+                if self.frequency(&candidate) > self.frequency(&victim) {
+                    Some((victim, Some(candidate)))
+                } else {
+                    Some((candidate, None))
+                }
+            }
+            (Some(candidate), None) => Some((candidate, None)),
+            (None, Some(victim)) => Some((victim, None)),
+            (None, None) => self.first_evictable(window()).map(|id| (id, None)),
+        }
+    }
+
+    /// Applies the access to the resident page.
+    fn access(&mut self, id: F) {
+        let Some(region) = self.region_of(&id) else {
+            return;
+        };
+        self.increment(&id);
+        self.climb(true);
+
+        match region {
+            Region::Window => self.move_to(id, Region::Window),
+            Region::Probation | Region::Protected => {
+                self.move_to(id, Region::Protected);
+                self.balance_protected();
+            }
+        }
+    }
+
+    /// Records the access for the hill climber, adjusting the window size
+    /// once the sample is complete.
+    fn climb(&mut self, hit: bool) {
+        let capacity = self.config().capacity;
+        let Some(step) = self
+            .climber()
+            .and_then(|climber| climber.record(hit, capacity))
+        else {
+            return;
+        };
+        let target = (self.window_target() as f64 + step).round() as usize;
+        self.set_window_target(target.clamp(1, capacity.saturating_sub(1).max(1)));
+        self.balance_protected();
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    fn list(&self, region: Region) -> &FrameList<F> {
+        match region {
+            Region::Window => &self.window,
+            Region::Probation => &self.probation,
+            Region::Protected => &self.protected,
+        }
+    }
+
+    fn region(&mut self, region: Region) -> &mut FrameList<F> {
+        match region {
+            Region::Window => &mut self.window,
+            Region::Probation => &mut self.probation,
+            Region::Protected => &mut self.protected,
+        }
+    }
+
+    /// Moves the least recently used window pages into the main region, while
+    /// the window exceeds its target size and the main region has room.
+    fn migrate(&mut self) {
+        while self.window.len() > self.window_target
+            && self.probation.len() + self.protected.len() < self.main_capacity()
+        {
+            let Some(id) = self.window.front().cloned() else {
+                break;
+            };
+            self.move_to(id, Region::Probation);
+        }
+    }
+}
+
+impl<F: FrameId> Regions<F> for Inner<F> {
+    fn config(&self) -> &WTinyLfuConfig {
+        &self.config
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn window_target(&self) -> usize {
+        self.window_target
+    }
+
+    fn set_window_target(&mut self, target: usize) {
+        self.window_target = target;
+    }
+
+    fn climber(&mut self) -> Option<&mut HillClimber> {
+        self.climber.as_mut()
+    }
+
+    fn region_of(&self, id: &F) -> Option<Region> {
+        self.pages.get(id).map(|page| page.region)
+    }
+
+    fn is_evictable(&self, id: &F) -> bool {
+        self.pages.get(id).is_some_and(|page| page.evictable)
+    }
+
+    fn len(&self, region: Region) -> usize {
+        self.list(region).len()
+    }
+
+    fn iter(&self, region: Region) -> impl Iterator<Item = F> + '_ {
+        self.list(region).iter().cloned()
+    }
+
+    fn move_to(&mut self, id: F, region: Region) {
+        let Some(page) = self.pages.get_mut(&id) else {
+            return;
+        };
+        let from = std::mem::replace(&mut page.region, region);
+        if from != region {
+            self.region(from).remove(&id);
+        }
+        self.region(region).push_back(id);
+    }
+
+    fn frequency(&self, id: &F) -> u8 {
+        self.sketch.frequency(id)
+    }
+
+    fn increment(&mut self, id: &F) {
+        self.sketch.increment(id);
+    }
+}
+
+/// Regions as they would be after applying the pending accesses, computed
+/// without modifying the replacer.
+///
+/// Pages moved by the accesses are appended to the tails of their new regions,
+/// while the rest of each region keeps its stored order.
+struct Simulation<'a, F: FrameId> {
+    inner: &'a Inner<F>,
+
+    window_target: usize,
+    climber: Option<HillClimber>,
+
+    /// Accesses recorded in the frequency sketch, in order.
+    increments: Vec<F>,
+
+    /// Region and sequence number of the latest move of each moved page.
+    moved: HashMap<F, (Region, usize)>,
+
+    /// Pages moved into each region, in order, along with the sequence numbers
+    /// of the moves. Entries of pages which were moved again later are stale.
+    tails: [Vec<(F, usize)>; 3],
+
+    /// Number of pages in each region.
+    lens: [usize; 3],
+
+    /// Number of moves so far.
+    moves: usize,
+}
+
+impl<'a, F: FrameId> Simulation<'a, F> {
+    fn new(inner: &'a Inner<F>) -> Self {
+        Self {
+            inner,
+            window_target: inner.window_target,
+            climber: inner.climber.clone(),
+            increments: Vec::new(),
+            moved: HashMap::new(),
+            tails: Default::default(),
+            lens: [Region::Window, Region::Probation, Region::Protected]
+                .map(|region| inner.list(region).len()),
+            moves: 0,
+        }
+    }
+}
+
+impl<F: FrameId> Regions<F> for Simulation<'_, F> {
+    fn config(&self) -> &WTinyLfuConfig {
+        &self.inner.config
+    }
+
+    fn size(&self) -> usize {
+        self.inner.size
+    }
+
+    fn window_target(&self) -> usize {
+        self.window_target
+    }
+
+    fn set_window_target(&mut self, target: usize) {
+        self.window_target = target;
+    }
+
+    fn climber(&mut self) -> Option<&mut HillClimber> {
+        self.climber.as_mut()
+    }
+
+    fn region_of(&self, id: &F) -> Option<Region> {
+        match self.moved.get(id) {
+            Some(&(region, _)) => Some(region),
+            None => self.inner.region_of(id),
+        }
+    }
+
+    fn is_evictable(&self, id: &F) -> bool {
+        self.inner.is_evictable(id)
+    }
+
+    fn len(&self, region: Region) -> usize {
+        self.lens[region as usize]
+    }
+
+    fn iter(&self, region: Region) -> impl Iterator<Item = F> + '_ {
+        let stored = self
+            .inner
+            .list(region)
+            .iter()
+            .filter(|id| !self.moved.contains_key(*id));
+        let tail = self.tails[region as usize]
+            .iter()
+            .filter(|(id, seq)| self.moved.get(id).is_some_and(|&(_, last)| last == *seq))
+            .map(|(id, _)| id);
+        stored.chain(tail).cloned()
+    }
+
+    fn move_to(&mut self, id: F, region: Region) {
+        let Some(from) = self.region_of(&id) else {
+            return;
+        };
+        self.lens[from as usize] -= 1;
+        self.lens[region as usize] += 1;
+        self.moves += 1;
+        self.moved.insert(id.clone(), (region, self.moves));
+        self.tails[region as usize].push((id, self.moves));
+    }
+
+    fn frequency(&self, id: &F) -> u8 {
+        self.inner.sketch.frequency_after(id, &self.increments)
+    }
+
+    fn increment(&mut self, id: &F) {
+        self.increments.push(id.clone());
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for WTinyLfuReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.write();
+        inner.migrate();
+        let (id, candidate) = inner.victim()?;
+
+        let page = inner.pages.remove(&id)?;
+        inner.region(page.region).remove(&id);
+        inner.size -= 1;
+        if let Some(candidate) = candidate {
+            inner.move_to(candidate, Region::Probation);
+        }
+
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        let inner = self.inner.read();
+        let pending = inner.pending.lock().clone();
+        if pending.is_empty() {
+            return inner.victim().map(|(id, _)| id);
+        }
+
+        // The pending accesses may reorder the regions and change the admission
+        // decision, so they are applied to a simulation of the regions instead.
+        let mut simulation = Simulation::new(&inner);
+        for id in pending {
+            simulation.access(id);
+        }
+        simulation.victim().map(|(id, _)| id)
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        // Fast path: the frame is resident, record the access to be applied later.
+        {
+            let inner = self.inner.read();
+            if inner.pages.contains_key(&id) {
+                let mut pending = inner.pending.lock();
+                if pending.len() < MAX_PENDING_ACCESSES {
+                    pending.push(id);
+                    return Ok(());
+                }
+            }
+        }
+
+        let mut inner = self.write();
+        if inner.pages.contains_key(&id) {
+            inner.access(id);
+            return Ok(());
+        }
+
+        if inner.pages.len() >= inner.config.capacity {
+            return Err(EvictError::FrameReplacerFull);
+        }
+        inner.sketch.increment(&id);
+        inner.climb(false);
+        inner.pages.insert(id.clone(), PageInfo {
+            region: Region::Window,
+            evictable: true,
+        });
+        inner.window.push_back(id);
+        inner.size += 1;
+        inner.migrate();
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // W-TinyLFU does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.write();
+
+        let page = inner
+            .pages
+            .get_mut(&id)
+            .ok_or(EvictError::InvalidFrameId(id))?;
+
+        // No-op if the frame is already in the desired state.
+        if !std::mem::replace(&mut page.evictable, false) {
+            return Ok(());
+        }
+        inner.size -= 1;
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(page) = inner.pages.get_mut(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+
+        // No-op if the frame is already in the desired state.
+        if std::mem::replace(&mut page.evictable, true) {
+            return Ok(());
+        }
+        inner.size += 1;
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.write();
+
+        let Some(page) = inner.pages.get(&id) else {
+            return Ok(());
+        };
+        if !page.evictable {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+        let region = page.region;
+        inner.region(region).remove(&id);
+        inner.pages.remove(&id);
+        inner.size -= 1;
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().size
+    }
+}
@@ -0,0 +1,343 @@
+//! WSClock page replacement algorithm.
+//!
+//! The algorithm implemented here is based on the "WSCLOCK — A Simple and
+//! Effective Algorithm for Virtual Memory Management" paper by Carr and
+//! Hennessy.
+
+use {
+    crate::{AccessType, EvictError, EvictResult, EvictionPolicy, FrameId},
+    hlc_gen::HlcGenerator,
+    parking_lot::RwLock,
+    std::{
+        collections::HashMap,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+    },
+};
+
+/// Configuration of the WSClock replacer.
+#[derive(Debug)]
+pub struct WsClockConfig {
+    /// Maximum number of frames that can be stored in the replacer.
+    pub capacity: usize,
+
+    /// Working set window (in milliseconds).
+    ///
+    /// Frames used within the window are considered to be in the working set,
+    /// and are evicted only if no frame outside of the working set is found.
+    pub tau: i64,
+}
+
+impl Default for WsClockConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            tau: 1_000,
+        }
+    }
+}
+
+/// State of a slot in the clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// No frame is stored in the slot.
+    Empty,
+
+    /// Frame is stored, but is not a candidate for eviction.
+    Pinned,
+
+    /// Frame is stored, and can be evicted.
+    Evictable,
+}
+
+/// Slot of the clock.
+#[derive(Debug)]
+struct Slot {
+    /// Reference bit, set on every access.
+    ///
+    /// The bit is atomic, so that it can be set while holding a read lock.
+    referenced: AtomicBool,
+
+    /// Time of the last use (in milliseconds), as observed by the hand.
+    last_use: i64,
+
+    state: State,
+}
+
+impl Slot {
+    /// Time of the last use, assuming that the hand passes the slot at `now`.
+    fn last_use(&self, now: i64) -> i64 {
+        if self.referenced.load(Ordering::Relaxed) {
+            now
+        } else {
+            self.last_use
+        }
+    }
+}
+
+/// WSClock frame replacer.
+///
+/// Works as [`ClockReplacer`](crate::ClockReplacer), but every frame also
+/// keeps the time of its last use. When the hand passes a referenced frame,
+/// it clears its reference bit, and records the current time as the time of
+/// its last use. A non-referenced frame is evicted only if it has not been
+/// used within the working set window (see [`WsClockConfig::tau`]), i.e. if it
+/// is no longer in the working set.
+///
+/// If all the frames are in the working set, the one used the longest time
+/// ago is evicted, once the hand completes the revolution.
+///
+/// Time is taken from the same hybrid logical clock the other replacers use
+/// to order accesses, but only the physical part of timestamps matters.
+/// Accessing a frame which is already stored only sets its reference bit, so
+/// it requires a read lock only.
+///
+/// Pinned frames stay in their slots, but are skipped by the hand.
+pub struct WsClockReplacer<F: FrameId> {
+    inner: Arc<RwLock<Inner<F>>>,
+}
+
+struct Inner<F: FrameId> {
+    /// Configuration of the replacer.
+    config: WsClockConfig,
+
+    slots: Vec<Slot>,
+
+    /// Position of the clock hand.
+    hand: usize,
+
+    /// Number of evictable frames.
+    size: usize,
+
+    /// Mapping of frame IDs to their slots.
+    ids: HashMap<F, usize>,
+
+    /// Frame IDs stored in slots.
+    frames: Vec<Option<F>>,
+
+    /// Empty slots.
+    free: Vec<usize>,
+
+    /// Monotonically increasing sequence of timestamps.
+    /// Used to determine the time of page uses.
+    seq: HlcGenerator,
+}
+
+impl<F: FrameId> Default for WsClockReplacer<F> {
+    fn default() -> Self {
+        Self::with_config(WsClockConfig::default())
+    }
+}
+
+impl<F: FrameId> WsClockReplacer<F> {
+    /// Creates a new WSClock replacer with the given capacity and working set
+    /// window (in milliseconds).
+    pub fn new(capacity: usize, tau: i64) -> Self {
+        Self::with_config(WsClockConfig { capacity, tau })
+    }
+
+    /// Creates a new WSClock replacer with the given configuration.
+    pub fn with_config(config: WsClockConfig) -> Self {
+        let capacity = config.capacity;
+        Self {
+            inner: Arc::new(RwLock::new(Inner {
+                config,
+                slots: (0..capacity)
+                    .map(|_| Slot {
+                        referenced: AtomicBool::new(false),
+                        last_use: 0,
+                        state: State::Empty,
+                    })
+                    .collect(),
+                hand: 0,
+                size: 0,
+                ids: HashMap::with_capacity(capacity),
+                frames: vec![None; capacity],
+                // Slots are taken from the back, start with the lowest one.
+                free: (0..capacity).rev().collect(),
+                seq: HlcGenerator::default(),
+            })),
+        }
+    }
+}
+
+impl<F: FrameId> Inner<F> {
+    /// Current time (in milliseconds).
+    fn now(&self) -> Option<i64> {
+        self.seq.next_timestamp().map(|ts| ts.timestamp())
+    }
+
+    fn set_state(&mut self, slot: usize, state: State) {
+        let s = &mut self.slots[slot];
+        match (s.state, state) {
+            (State::Evictable, State::Pinned | State::Empty) => self.size -= 1,
+            (State::Pinned | State::Empty, State::Evictable) => self.size += 1,
+            _ => {}
+        }
+        s.state = state;
+    }
+
+    /// Evictable slots, in the order the hand passes them.
+    fn evictable(&self) -> impl Iterator<Item = usize> + '_ {
+        let n = self.slots.len();
+        (0..n)
+            .map(move |i| (self.hand + i) % n)
+            .filter(|&slot| self.slots[slot].state == State::Evictable)
+    }
+
+    /// Finds the slot the hand stops at, without moving the hand.
+    ///
+    /// The hand stops at the first evictable non-referenced slot outside of
+    /// the working set. If there is no such slot, the hand completes the
+    /// revolution, and stops at the slot used the longest time ago. Whether
+    /// the revolution is completed is returned along with the slot.
+    fn victim(&self, now: i64) -> Option<(usize, bool)> {
+        if self.size == 0 {
+            return None;
+        }
+        let tau = self.config.tau;
+        let mut oldest: Option<(usize, i64)> = None;
+        for slot in self.evictable() {
+            let last_use = self.slots[slot].last_use(now);
+            if now - last_use > tau {
+                return Some((slot, false));
+            }
+            if oldest.is_none_or(|(_, oldest)| last_use < oldest) {
+                oldest = Some((slot, last_use));
+            }
+        }
+        oldest.map(|(slot, _)| (slot, true))
+    }
+
+    /// Moves the hand, clearing reference bits, until a victim is found.
+    ///
+    /// The victim's slot is emptied.
+    fn evict(&mut self, now: i64) -> Option<usize> {
+        let (victim, revolution) = self.victim(now)?;
+
+        // Record the time of the last use of the referenced frames the hand passes.
+        let n = self.slots.len();
+        let passed = if revolution {
+            n
+        } else {
+            (victim + n - self.hand) % n
+        };
+        for i in 0..passed {
+            let s = &mut self.slots[(self.hand + i) % n];
+            if s.state == State::Evictable && std::mem::take(s.referenced.get_mut()) {
+                s.last_use = now;
+            }
+        }
+
+        self.hand = (victim + 1) % n;
+        self.set_state(victim, State::Empty);
+        Some(victim)
+    }
+}
+
+impl<F: FrameId> EvictionPolicy<F> for WsClockReplacer<F> {
+    type Error = EvictError<F>;
+
+    fn evict(&self) -> Option<F> {
+        let mut inner = self.inner.write();
+        let now = inner.now()?;
+        let slot = inner.evict(now)?;
+        let id = inner.frames[slot].take()?;
+        inner.ids.remove(&id);
+        inner.free.push(slot);
+        Some(id)
+    }
+
+    fn peek(&self) -> Option<F> {
+        let inner = self.inner.read();
+        let now = inner.now()?;
+        inner
+            .victim(now)
+            .and_then(|(slot, _)| inner.frames[slot].clone())
+    }
+
+    fn touch(&self, id: F) -> EvictResult<(), F> {
+        // Fast path: the frame is already stored, just set its reference bit.
+        {
+            let inner = self.inner.read();
+            if let Some(&slot) = inner.ids.get(&id) {
+                inner.slots[slot].referenced.store(true, Ordering::Relaxed);
+                return Ok(());
+            }
+        }
+
+        let mut inner = self.inner.write();
+        if let Some(&slot) = inner.ids.get(&id) {
+            inner.slots[slot].referenced.store(true, Ordering::Relaxed);
+            return Ok(());
+        }
+
+        if inner.free.is_empty() {
+            return Err(EvictError::FrameReplacerFull);
+        }
+        let now = inner.now().ok_or(EvictError::SequenceExhausted)?;
+        let slot = inner.free.pop().ok_or(EvictError::FrameReplacerFull)?;
+        let s = &mut inner.slots[slot];
+        *s.referenced.get_mut() = false;
+        s.last_use = now;
+        inner.set_state(slot, State::Evictable);
+        inner.frames[slot] = Some(id.clone());
+        inner.ids.insert(id, slot);
+
+        Ok(())
+    }
+
+    fn touch_with<T: AccessType>(&self, id: F, _access_type: T) -> EvictResult<(), F> {
+        // WSClock does not use access type.
+        self.touch(id)
+    }
+
+    fn pin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let slot = *inner.ids.get(&id).ok_or(EvictError::InvalidFrameId(id))?;
+        inner.set_state(slot, State::Pinned);
+
+        Ok(())
+    }
+
+    fn unpin(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        // Unpinning unknown frame registers it.
+        let Some(&slot) = inner.ids.get(&id) else {
+            drop(inner);
+            return self.touch(id);
+        };
+        inner.set_state(slot, State::Evictable);
+
+        Ok(())
+    }
+
+    fn remove(&self, id: F) -> EvictResult<(), F> {
+        let mut inner = self.inner.write();
+
+        let Some(&slot) = inner.ids.get(&id) else {
+            return Ok(());
+        };
+        if inner.slots[slot].state == State::Pinned {
+            return Err(EvictError::PinnedFrameRemoval(id));
+        }
+        inner.set_state(slot, State::Empty);
+        inner.frames[slot] = None;
+        inner.ids.remove(&id);
+        inner.free.push(slot);
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.read().config.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.inner.read().size
+    }
+}
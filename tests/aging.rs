@@ -0,0 +1,141 @@
+use {
+    evict::{AgingConfig, AgingReplacer, EvictError, EvictionPolicy},
+    std::sync::Arc,
+};
+
+#[test]
+fn basic_ops() {
+    let replacer = AgingReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: all frames have equal registers, so they are evicted in order.
+    assert_eq!(replacer.peek(), Some(1));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.size(), 2);
+
+    // Scenario: frame 2 is referenced since the last tick, so frame 3 goes first.
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.peek(), Some(3));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn aging() {
+    let replacer = AgingReplacer::new(3);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+
+    // Scenario: registers are 0b0100_0000, 0b1100_0000 and 0b1100_0000.
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    replacer.tick();
+
+    // Scenario: registers are 0b0010_0000, 0b0110_0000 and 0b1110_0000, i.e. the
+    // more recently a frame is referenced, the longer it stays.
+    replacer.touch(3).unwrap();
+    replacer.tick();
+    replacer.touch(1).unwrap(); // Not shifted in yet, only breaks the ties.
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+}
+
+#[test]
+fn nru() {
+    let replacer = AgingReplacer::with_config(AgingConfig {
+        capacity: 3,
+        bits: 1,
+    });
+
+    // Scenario: with a single bit, only references during the last tick
+    // matter.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    replacer.touch(1).unwrap();
+    replacer.tick();
+    replacer.tick();
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(2));
+}
+
+#[test]
+fn pinned_frames() {
+    let replacer = AgingReplacer::new(3);
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+
+    // Scenario: registers are 0b0010_0000, 0b0110_0000 and 0b1110_0000, but
+    // frame 1 is pinned, so frame 2 is the victim.
+    replacer.pin(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    replacer.tick();
+    replacer.touch(3).unwrap();
+    replacer.tick();
+    assert_eq!(replacer.size(), 2);
+    assert_eq!(replacer.remove(1), Err(EvictError::PinnedFrameRemoval(1)));
+    assert_eq!(replacer.peek(), Some(2));
+
+    // Scenario: pinned frame kept aging, so it goes first once unpinned.
+    replacer.unpin(1).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+}
+
+#[test]
+fn multi_threaded() {
+    use std::thread;
+
+    let n = 100;
+    let k = 20;
+    let replacer = Arc::new(AgingReplacer::new(n * k));
+
+    // Concurrently register frames, and then access them concurrently again (which
+    // only requires a read lock), while another thread drives the ticks.
+    let mut handles = vec![];
+    for i in 0..n {
+        let replacer = Arc::clone(&replacer);
+        handles.push(thread::spawn(move || {
+            for j in 0..k {
+                replacer.unpin(i * k + j).unwrap();
+                replacer.touch(i * k + j).unwrap();
+            }
+        }));
+    }
+    let ticker = {
+        let replacer = Arc::clone(&replacer);
+        thread::spawn(move || {
+            for _ in 0..100 {
+                replacer.tick();
+            }
+        })
+    };
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    ticker.join().unwrap();
+    assert_eq!(replacer.size(), n * k);
+
+    for _ in 0..n * k {
+        assert!(replacer.evict().is_some());
+    }
+    assert_eq!(replacer.size(), 0);
+}
@@ -0,0 +1,121 @@
+use evict::{ArcReplacer, EvictError, EvictionPolicy};
+
+#[test]
+fn basic_ops() {
+    let replacer = ArcReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.size(), 0);
+    assert_eq!(replacer.evict(), None);
+
+    // Scenario: add three frames, all of them end up in `T1`.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+
+    // Scenario: the least recently used frame of `T1` is evicted first.
+    assert_eq!(replacer.peek(), Some(1));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.size(), 2);
+
+    // Scenario: frame 2 is referenced again, so it is moved to `T2`. Frame 3 is the
+    // only one left in `T1`, and it is evicted next.
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn over_capacity() {
+    let replacer = ArcReplacer::new(3);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    // Next touch should fail since the replacer is full.
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Touching resident frames is still fine.
+    replacer.touch(1).unwrap();
+
+    // Once a frame is evicted, there is room for a new one.
+    assert_eq!(replacer.evict(), Some(2));
+    replacer.touch(4).unwrap();
+    assert_eq!(replacer.size(), 3);
+}
+
+#[test]
+fn ghost_hits_adapt_target() {
+    let replacer = ArcReplacer::new(2);
+
+    // Frame 1 is evicted from `T1` and remembered in `B1`.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+
+    // Scenario: ghost hit in `B1` grows the target size of `T1`, and frame 1 is
+    // brought back into `T2`. Now `T1` is not above its target, so the victim is
+    // taken from `T2`.
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+
+    // Frame 3 joins frame 2 in `T1`, which is now above its target.
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: ghost hit in `B2` shrinks the target size of `T1` back, so
+    // victims are again taken from `T1`.
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(1));
+}
+
+#[test]
+fn scan_resistance() {
+    let replacer = ArcReplacer::new(4);
+
+    // Frames 1 and 2 are hot, i.e. referenced more than once.
+    for id in [1, 2, 1, 2] {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: a long scan of frames referenced only once does not flush hot
+    // frames out of the replacer.
+    for id in 10..100 {
+        replacer.touch(id).unwrap();
+        assert_eq!(replacer.evict(), Some(id));
+    }
+    assert_eq!(replacer.size(), 2);
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+}
+
+#[test]
+fn pinned_frames() {
+    let replacer = ArcReplacer::new(3);
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+
+    // Frame 1 is in `T1`, frame 2 is referenced again, so it is in `T2`.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(2).unwrap();
+
+    // Scenario: the head of `T1` is pinned, so the victim is taken from `T2`, even
+    // though `T1` is above its target. Pinned frame cannot be removed.
+    replacer.pin(1).unwrap();
+    assert_eq!(replacer.size(), 1);
+    assert_eq!(replacer.remove(1), Err(EvictError::PinnedFrameRemoval(1)));
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), None);
+
+    // Scenario: once unpinned, frame 1 is evicted from `T1`, and removing it
+    // afterwards has no effect.
+    replacer.unpin(1).unwrap();
+    assert_eq!(replacer.size(), 1);
+    assert_eq!(replacer.evict(), Some(1));
+    replacer.remove(1).unwrap();
+    assert_eq!(replacer.size(), 0);
+}
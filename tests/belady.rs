@@ -0,0 +1,102 @@
+use {
+    evict::{BeladyReplacer, EvictError, EvictionPolicy},
+    std::collections::HashSet,
+};
+
+/// Reference string from the classic textbook example.
+const ACCESSES: [usize; 20] = [7, 0, 1, 2, 0, 3, 0, 4, 2, 3, 0, 3, 2, 1, 2, 0, 1, 7, 0, 1];
+
+/// Number of misses when accessing frames in the given order.
+fn misses(replacer: &impl EvictionPolicy<usize>, accesses: &[usize]) -> usize {
+    let mut resident = HashSet::new();
+    let mut misses = 0;
+    for &id in accesses {
+        if !resident.contains(&id) {
+            misses += 1;
+            if resident.len() == replacer.capacity() {
+                resident.remove(&replacer.evict().unwrap());
+            }
+            resident.insert(id);
+        }
+        replacer.touch(id).unwrap();
+    }
+    misses
+}
+
+#[test]
+fn basic_ops() {
+    let replacer = BeladyReplacer::new(3, [1, 2, 3, 1, 3, 2]);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: frame 2 is accessed the last, so it is evicted first.
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: once frame 1 is accessed for the last time, it goes next.
+    assert_eq!(replacer.peek(), Some(3));
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn optimal() {
+    // Scenario: textbook example, OPT has 9 misses (LRU would have 12).
+    let replacer = BeladyReplacer::new(3, ACCESSES);
+    assert_eq!(misses(&replacer, &ACCESSES), 9);
+    assert_eq!(replacer.size(), 3);
+}
+
+#[test]
+fn unpin_unknown() {
+    let replacer = BeladyReplacer::new(2, [1, 2, 1]);
+
+    // Scenario: unpinning unknown frame registers it, but does not consume its
+    // access, so the next access to frame 1 is still the one at position 2.
+    replacer.unpin(1).unwrap();
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.size(), 2);
+    assert_eq!(replacer.peek(), Some(2));
+
+    // Scenario: once frame 1 is accessed for the last time, it goes first (as
+    // the least recently used one among the frames never accessed again).
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), None);
+}
+
+#[test]
+fn pinned_frames() {
+    let replacer = BeladyReplacer::new(3, [1, 2, 3, 1, 3, 2]);
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+
+    // Scenario: frame 2 is accessed the last, but it is pinned, so the frame
+    // accessed next to last goes instead.
+    replacer.pin(2).unwrap();
+    assert_eq!(replacer.size(), 2);
+    assert_eq!(replacer.remove(2), Err(EvictError::PinnedFrameRemoval(2)));
+    assert_eq!(replacer.peek(), Some(3));
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: once unpinned, frame 2 is the one accessed the last again.
+    replacer.unpin(2).unwrap();
+    assert_eq!(replacer.size(), 2);
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), None);
+}
@@ -0,0 +1,158 @@
+use {
+    evict::{CarConfig, CarReplacer, EvictError, EvictionPolicy},
+    std::sync::Arc,
+};
+
+#[test]
+fn basic_ops() {
+    let replacer = CarReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    // Scenario: new frames go into T1.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: referenced frame 1 is moved into T2 by the hand of T1, and frame 2
+    // is picked as a victim instead.
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: T1 is empty, victim is taken from T2.
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn ghost_hits() {
+    let replacer = CarReplacer::new(2);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+
+    // Scenario: frame 1 is remembered in B1, so once accessed again, it goes
+    // straight into T2. Target size of T1 is increased.
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: new frame 3 is evicted before the older frame 1, which has been
+    // accessed twice.
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), None);
+}
+
+#[test]
+fn temporal_filtering() {
+    let replacer = CarReplacer::with_config(CarConfig {
+        capacity: 3,
+        temporal_filtering: true,
+    });
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+
+    // Scenario: referenced frame 1 is recycled within T1 and is marked as a
+    // long-term one, so it is moved into T2 on the next sweep.
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(1));
+
+    // Scenario: frames re-referenced from history have long-term utility, so they
+    // are kept, while the newer frame 4 is evicted.
+    replacer.touch(2).unwrap();
+    replacer.touch(1).unwrap();
+    replacer.touch(4).unwrap();
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), None);
+}
+
+#[test]
+fn peek_is_read_only() {
+    let replacer = CarReplacer::new(3);
+    for id in [1, 2, 3, 1, 2] {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: the hand of `T1` would move referenced frames 1 and 2 into `T2`
+    // before it stops at frame 3. Peeking finds the same victim, without moving
+    // the hand.
+    assert_eq!(replacer.peek(), Some(3));
+    assert_eq!(replacer.peek(), Some(3));
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: the same trace results in the same victims, whether peeking
+    // before every access or not. Some of the frames are pinned for a while, so
+    // that the hands skip over them.
+    for temporal_filtering in [false, true] {
+        let config = || CarConfig {
+            capacity: 8,
+            temporal_filtering,
+        };
+        let peeking = CarReplacer::with_config(config());
+        let replacer = CarReplacer::with_config(config());
+        let mut pinned = None;
+        for id in (0..500).map(|i: i32| (i * i + i / 7) % 13) {
+            let victim = peeking.peek();
+            if replacer.touch(id).is_err() {
+                assert_eq!(replacer.evict(), victim);
+                assert_eq!(peeking.evict(), victim);
+                replacer.touch(id).unwrap();
+            }
+            peeking.touch(id).unwrap();
+            if id % 3 == 0 {
+                for r in [&peeking, &replacer] {
+                    if let Some(pinned) = pinned {
+                        r.unpin(pinned).unwrap();
+                    }
+                    r.pin(id).unwrap();
+                }
+                pinned = Some(id);
+            }
+        }
+    }
+}
+
+#[test]
+fn multi_threaded() {
+    use std::thread;
+
+    let n = 100;
+    let k = 20;
+    let replacer = Arc::new(CarReplacer::with_temporal_filtering(n * k));
+
+    // Concurrently register frames, and then access them concurrently again (which
+    // only requires a read lock).
+    let mut handles = vec![];
+    for i in 0..n {
+        let replacer = Arc::clone(&replacer);
+        handles.push(thread::spawn(move || {
+            for j in 0..k {
+                replacer.unpin(i * k + j).unwrap();
+                replacer.touch(i * k + j).unwrap();
+            }
+        }));
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(replacer.size(), n * k);
+
+    // Every frame is referenced, so the hand has to sweep over all of them.
+    for _ in 0..n * k {
+        assert!(replacer.evict().is_some());
+    }
+    assert_eq!(replacer.size(), 0);
+}
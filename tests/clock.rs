@@ -0,0 +1,114 @@
+use {
+    evict::{ClockReplacer, DenseClockReplacer, EvictError, EvictionPolicy},
+    std::sync::Arc,
+};
+
+#[test]
+fn basic_ops() {
+    let replacer = ClockReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    // Scenario: fill the clock, all frames have their reference bits set.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: the hand clears all the bits during the first revolution, and
+    // stops at frame 1.
+    assert_eq!(replacer.peek(), Some(1));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.size(), 2);
+
+    // Scenario: frame 2 is referenced again, so it gets a second chance. New frame
+    // 4 takes the slot of frame 1.
+    replacer.touch(2).unwrap();
+    replacer.touch(4).unwrap();
+    assert_eq!(replacer.peek(), Some(3));
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: the hand wraps around, frame 4 gets a second chance as well.
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn second_chance() {
+    let replacer = ClockReplacer::new(4);
+    for id in 1..=4 {
+        replacer.touch(id).unwrap();
+    }
+    assert_eq!(replacer.evict(), Some(1));
+
+    // Scenario: frame 2 is referenced after the hand cleared its bit, so the hand
+    // passes it once more, and stops at frame 3.
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: the hand keeps going from where it stopped, frame 2 has already
+    // used up its second chance.
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), None);
+}
+
+#[test]
+fn dense_frame_ids() {
+    let replacer = DenseClockReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+
+    // Scenario: frame IDs are used as slots directly, so IDs out of range are
+    // rejected.
+    assert_eq!(replacer.touch(3), Err(EvictError::InvalidFrameId(3)));
+    replacer.touch(0).unwrap();
+    replacer.touch(1).unwrap();
+    replacer.unpin(2).unwrap();
+    assert_eq!(replacer.size(), 3);
+
+    assert_eq!(replacer.evict(), Some(0));
+    replacer.touch(1).unwrap();
+    replacer.touch(0).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: pinned frames are skipped by the hand.
+    replacer.pin(1).unwrap();
+    assert_eq!(replacer.size(), 1);
+    assert_eq!(replacer.remove(1), Err(EvictError::PinnedFrameRemoval(1)));
+    assert_eq!(replacer.evict(), Some(0));
+    assert_eq!(replacer.evict(), None);
+
+    replacer.unpin(1).unwrap();
+    replacer.remove(1).unwrap();
+    assert_eq!(replacer.size(), 0);
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+}
+
+#[test]
+fn multi_threaded() {
+    use std::thread;
+
+    let n = 100;
+    let k = 20;
+    let replacer = Arc::new(DenseClockReplacer::new(n * k));
+
+    // Concurrently register frames, and then access them concurrently again (which
+    // only requires a read lock).
+    let mut handles = vec![];
+    for i in 0..n {
+        let replacer = Arc::clone(&replacer);
+        handles.push(thread::spawn(move || {
+            for j in 0..k {
+                replacer.unpin(i * k + j).unwrap();
+                replacer.touch(i * k + j).unwrap();
+            }
+        }));
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(replacer.size(), n * k);
+}
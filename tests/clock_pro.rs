@@ -0,0 +1,181 @@
+use {
+    evict::{ClockProReplacer, EvictError, EvictionPolicy},
+    std::{collections::HashSet, sync::Arc},
+};
+
+#[test]
+fn basic_ops() {
+    let replacer = ClockProReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    // Scenario: new pages are resident cold pages in their test period.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: non-referenced cold page is evicted, but is kept as a non-resident
+    // page till its test period is over.
+    assert_eq!(replacer.peek(), Some(1));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.size(), 2);
+
+    // Scenario: page 1 is accessed during its test period, so it comes back as a
+    // hot page. Page 2 is accessed during its test period as well, and is
+    // promoted once `HAND_cold` reaches it.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: only hot pages are left, so `HAND_hot` demotes them to cold, before
+    // they can be evicted.
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn scan_resistance() {
+    let replacer = ClockProReplacer::new(4);
+
+    // Pages 1 and 2 are accessed during their test periods, so they are promoted
+    // to hot pages on the first eviction.
+    for id in [1, 2, 3, 4, 1, 2] {
+        replacer.touch(id).unwrap();
+    }
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: a long scan of pages accessed only once does not flush hot pages
+    // out of the replacer.
+    for id in 10..100 {
+        replacer.touch(id).unwrap();
+        let victim = replacer.evict().unwrap();
+        assert!(victim != 1 && victim != 2);
+    }
+    assert_eq!(replacer.size(), 3);
+}
+
+#[test]
+fn pin_and_remove() {
+    let replacer = ClockProReplacer::new(3);
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+
+    // Scenario: pinned page is kept, but is never picked as a victim.
+    replacer.pin(1).unwrap();
+    replacer.pin(1).unwrap(); // Pinning again has no effect.
+    assert_eq!(replacer.size(), 2);
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.remove(1), Err(EvictError::PinnedFrameRemoval(1)));
+
+    // Scenario: remove arbitrary page.
+    replacer.remove(2).unwrap();
+    assert_eq!(replacer.size(), 1);
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), None);
+
+    // Non-resident pages cannot be pinned.
+    assert_eq!(replacer.pin(3), Err(EvictError::InvalidFrameId(3)));
+
+    // Scenario: once unpinned, page becomes evictable again.
+    replacer.unpin(1).unwrap();
+    assert_eq!(replacer.size(), 1);
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn loop_resistance() {
+    let replacer = ClockProReplacer::new(100);
+
+    // Scenario: loop over a working set slightly larger than the capacity. LRU
+    // would get no hits at all, while CLOCK-Pro keeps hot pages resident.
+    let mut resident = HashSet::new();
+    let mut hits = 0;
+    for id in (0..20).flat_map(|_| 0..110) {
+        if resident.contains(&id) {
+            hits += 1;
+        } else if resident.len() == replacer.capacity() {
+            resident.remove(&replacer.evict().unwrap());
+        }
+        resident.insert(id);
+        replacer.touch(id).unwrap();
+    }
+    assert!(hits > 1000, "hits: {hits}");
+}
+
+#[test]
+fn peek_is_read_only() {
+    let replacer = ClockProReplacer::new(4);
+
+    // Scenario: pages 1 and 2 are accessed during their test periods, so
+    // `HAND_cold` promotes them to hot pages before it stops at page 3. Peeking
+    // finds the same victim, without moving the hand.
+    for id in [1, 2, 3, 4, 1, 2] {
+        replacer.touch(id).unwrap();
+    }
+    assert_eq!(replacer.peek(), Some(3));
+    assert_eq!(replacer.peek(), Some(3));
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: the only page is accessed during its test period, so `HAND_cold`
+    // promotes it to hot, and stays at it, until `HAND_hot` demotes it back.
+    let replacer = ClockProReplacer::new(6);
+    replacer.touch(2).unwrap();
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: the same trace results in the same victims, whether peeking
+    // before every access or not.
+    let peeking = ClockProReplacer::new(8);
+    let replacer = ClockProReplacer::new(8);
+    for id in (0..500).map(|i: i32| (i * i + i / 7) % 13) {
+        let victim = peeking.peek();
+        if replacer.touch(id).is_err() {
+            assert_eq!(replacer.evict(), victim);
+            assert_eq!(peeking.evict(), victim);
+            replacer.touch(id).unwrap();
+        }
+        peeking.touch(id).unwrap();
+    }
+}
+
+#[test]
+fn multi_threaded() {
+    use std::thread;
+
+    let n = 100;
+    let k = 20;
+    let replacer = Arc::new(ClockProReplacer::new(n * k));
+
+    // Concurrently register frames, and then access them concurrently again (which
+    // only requires a read lock).
+    let mut handles = vec![];
+    for i in 0..n {
+        let replacer = Arc::clone(&replacer);
+        handles.push(thread::spawn(move || {
+            for j in 0..k {
+                replacer.unpin(i * k + j).unwrap();
+                for _ in 0..5 {
+                    replacer.touch(i * k + j).unwrap();
+                }
+            }
+        }));
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(replacer.size(), n * k);
+
+    for _ in 0..n * k {
+        assert!(replacer.evict().is_some());
+    }
+    assert_eq!(replacer.size(), 0);
+}
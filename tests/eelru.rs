@@ -0,0 +1,92 @@
+use evict::{EelruReplacer, EvictError, EvictionPolicy, LruReplacer};
+
+/// Number of misses when accessing frames `0..n` cyclically.
+fn loop_misses(replacer: &impl EvictionPolicy<usize>, n: usize, rounds: usize) -> usize {
+    let mut misses = 0;
+    for _ in 0..rounds {
+        for id in 0..n {
+            if replacer.touch(id).is_err() {
+                misses += 1;
+                replacer.evict().unwrap();
+                replacer.touch(id).unwrap();
+            }
+        }
+    }
+    misses
+}
+
+#[test]
+fn basic_ops() {
+    let replacer = EelruReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: without loops, the replacer works as LRU.
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn large_loop() {
+    // Scenario: frames are accessed in a loop slightly larger than the replacer.
+    // LRU misses on every access, while EELRU switches to early eviction.
+    let lru = loop_misses(&LruReplacer::new(100), 120, 20);
+    let eelru = loop_misses(&EelruReplacer::new(100), 120, 20);
+    assert_eq!(lru, 120 * 20 - 100);
+    assert!(eelru < lru / 2);
+
+    // Scenario: loop fits into the replacer, so both have no misses.
+    assert_eq!(loop_misses(&EelruReplacer::new(100), 80, 20), 0);
+}
+
+#[test]
+fn adaptation() {
+    let replacer = EelruReplacer::new(100);
+
+    // Scenario: after a large loop, the access pattern becomes LRU friendly, and
+    // the replacer switches back to LRU.
+    loop_misses(&replacer, 120, 20);
+    for _ in 0..20 {
+        for id in 1000..1050 {
+            if replacer.touch(id).is_err() {
+                replacer.evict().unwrap();
+                replacer.touch(id).unwrap();
+            }
+        }
+    }
+    for _ in 0..50 {
+        assert!(replacer.evict().unwrap() < 1000);
+    }
+}
+
+#[test]
+fn pinned_frames() {
+    let replacer = EelruReplacer::new(10);
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+
+    // Scenario: after a large loop, the victim is taken from the early eviction
+    // point. Frame 6 is there, but it is pinned, so the next older frame goes
+    // instead.
+    loop_misses(&replacer, 12, 20);
+    assert_eq!(replacer.peek(), Some(6));
+    replacer.pin(6).unwrap();
+    assert_eq!(replacer.remove(6), Err(EvictError::PinnedFrameRemoval(6)));
+    assert_eq!(replacer.peek(), Some(5));
+
+    // Scenario: pinned frame kept its recency position, so it is the victim
+    // again once unpinned.
+    replacer.unpin(6).unwrap();
+    assert_eq!(replacer.evict(), Some(6));
+    assert_eq!(replacer.evict(), Some(5));
+}
@@ -0,0 +1,100 @@
+use evict::{EvictError, EvictionPolicy, FbrConfig, FbrReplacer};
+
+#[test]
+fn basic_ops() {
+    let replacer = FbrReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    // Scenario: each section holds a single frame, frame 1 is in the old one.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+    assert_eq!(replacer.peek(), Some(1));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.size(), 2);
+
+    // Scenario: frame 2 is referenced from the middle section, so it moves to the
+    // top of the stack, and frame 3 ends up in the old section.
+    replacer.touch(2).unwrap();
+    replacer.touch(4).unwrap();
+    assert_eq!(replacer.peek(), Some(3));
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: the old section is empty, victims are taken from the middle one.
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn frequency() {
+    let replacer = FbrReplacer::new(4);
+
+    // Scenario: frame 1 is referenced again from the middle section, so its count
+    // grows, while repeated references to frame 4 in the new section are not
+    // counted.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(1).unwrap();
+    replacer.touch(3).unwrap();
+    replacer.touch(4).unwrap();
+    replacer.touch(4).unwrap();
+    replacer.touch(4).unwrap();
+
+    // Scenario: frame 2 is referenced from the old section, so frames 3 and 1 are
+    // in the old section now, and frame 3 has the lower count, even though it is
+    // more recently used.
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), Some(2));
+}
+
+#[test]
+fn aging() {
+    let replacer = FbrReplacer::with_config(FbrConfig {
+        capacity: 4,
+        max_average_count: 1,
+        ..FbrConfig::default()
+    });
+
+    // Scenario: the same accesses as above, but counts are halved as soon as any
+    // frame is referenced twice, so the least recently used frame in the old
+    // section is evicted.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(1).unwrap();
+    replacer.touch(3).unwrap();
+    replacer.touch(4).unwrap();
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(3));
+}
+
+#[test]
+fn pinned_frames() {
+    let replacer = FbrReplacer::new(4);
+    for id in 1..=4 {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: frames 1 and 2 are in the old section, frame 1 is pinned, so
+    // frame 2 goes first. The old section has no evictable frames then, so the
+    // victim is taken from the middle one.
+    replacer.pin(1).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.peek(), Some(3));
+
+    // Scenario: pinned frame kept its position at the bottom of the stack, so it
+    // goes first once unpinned.
+    replacer.unpin(1).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), None);
+}
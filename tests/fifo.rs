@@ -0,0 +1,48 @@
+use evict::{EvictError, EvictionPolicy, FifoReplacer};
+
+#[test]
+fn basic_ops() {
+    let replacer = FifoReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: accesses do not affect the eviction order.
+    replacer.touch(1).unwrap();
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.peek(), Some(1));
+    assert_eq!(replacer.evict(), Some(1));
+
+    // Scenario: new frame goes to the end of the queue.
+    replacer.touch(4).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn pinned_frames_keep_position() {
+    let replacer = FifoReplacer::new(3);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+
+    // Scenario: pinned frame is skipped, but keeps its position in the queue.
+    replacer.pin(1).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+    replacer.touch(4).unwrap();
+
+    // Scenario: once unpinned, frame is evicted before the frames queued after it.
+    replacer.unpin(1).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(4));
+}
@@ -0,0 +1,130 @@
+use {
+    evict::{EvictError, EvictionPolicy, GclockConfig, GclockReplacer},
+    std::sync::Arc,
+};
+
+#[test]
+fn basic_ops() {
+    let replacer = GclockReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    // Scenario: fill the clock, all frames have their counters set to 1.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: the hand decrements all the counters during the first
+    // revolution, and stops at frame 1.
+    assert_eq!(replacer.peek(), Some(1));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.size(), 2);
+
+    // Scenario: frame 2 is referenced again, so it survives the next pass. New
+    // frame 4 takes the slot of frame 1.
+    replacer.touch(2).unwrap();
+    replacer.touch(4).unwrap();
+    assert_eq!(replacer.peek(), Some(3));
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: the hand wraps around, frame 4 survives one more pass.
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn frequency() {
+    let replacer = GclockReplacer::new(3);
+
+    // Scenario: frequently accessed frame survives several revolutions of the
+    // hand, while the frames accessed once are evicted.
+    for _ in 0..5 {
+        replacer.touch(1).unwrap();
+    }
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+    replacer.touch(4).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), Some(1));
+}
+
+#[test]
+fn max_count() {
+    let replacer = GclockReplacer::with_config(GclockConfig {
+        capacity: 3,
+        max_count: 1,
+    });
+
+    // Scenario: with the counter saturating at 1, GCLOCK works as Clock, i.e.
+    // frequency does not matter.
+    for _ in 0..5 {
+        replacer.touch(1).unwrap();
+    }
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+}
+
+#[test]
+fn pinned_frames() {
+    let replacer = GclockReplacer::new(3);
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+    for id in 1..=3 {
+        replacer.touch(id).unwrap();
+    }
+    assert_eq!(replacer.evict(), Some(1));
+    replacer.touch(4).unwrap();
+
+    // Scenario: the first revolution has left frames 2 and 3 with zero counters.
+    // Frame 2 is next to the hand, but it is pinned, so it is skipped.
+    replacer.pin(2).unwrap();
+    assert_eq!(replacer.remove(2), Err(EvictError::PinnedFrameRemoval(2)));
+    assert_eq!(replacer.peek(), Some(3));
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: pinned frame kept its zero counter, so once unpinned, it is
+    // evicted before frame 4, which the hand passes first.
+    replacer.unpin(2).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), None);
+}
+
+#[test]
+fn multi_threaded() {
+    use std::thread;
+
+    let n = 100;
+    let k = 20;
+    let replacer = Arc::new(GclockReplacer::new(n * k));
+
+    // Concurrently register frames, and then access them concurrently again (which
+    // only requires a read lock).
+    let mut handles = vec![];
+    for i in 0..n {
+        let replacer = Arc::clone(&replacer);
+        handles.push(thread::spawn(move || {
+            for j in 0..k {
+                replacer.unpin(i * k + j).unwrap();
+                replacer.touch(i * k + j).unwrap();
+            }
+        }));
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(replacer.size(), n * k);
+
+    for _ in 0..n * k {
+        assert!(replacer.evict().is_some());
+    }
+    assert_eq!(replacer.size(), 0);
+}
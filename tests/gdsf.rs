@@ -0,0 +1,91 @@
+use evict::{EvictError, EvictionPolicy, GdsfReplacer};
+
+#[test]
+fn basic_ops() {
+    let replacer = GdsfReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    // Scenario: with the same size and cost, the replacer works as LFU.
+    for id in [1, 2, 3, 1, 1, 2] {
+        replacer.touch(id).unwrap();
+    }
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    assert_eq!(replacer.peek(), Some(3));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn size_and_cost() {
+    let replacer = GdsfReplacer::new(4);
+
+    // Scenario: large frames are evicted before small ones, and cheap frames
+    // before expensive ones.
+    replacer.touch_with_cost(1, 1024, 1.0).unwrap();
+    replacer.touch_with_cost(2, 1, 1.0).unwrap();
+    replacer.touch_with_cost(3, 1024, 4096.0).unwrap();
+    replacer.touch_with_cost(4, 1, 0.5).unwrap();
+
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: regular touch keeps the size and cost supplied earlier.
+    replacer.touch_with_cost(1, 1024, 1.0).unwrap();
+    replacer.touch_with_cost(2, 1, 1.0).unwrap();
+    replacer.touch(1).unwrap();
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+}
+
+#[test]
+fn inflation() {
+    let replacer = GdsfReplacer::new(2);
+
+    // Scenario: frame 1 was hot long ago, but as frames are evicted, the
+    // inflation value rises, and new frames eventually overtake it.
+    for _ in 0..5 {
+        replacer.touch(1).unwrap();
+    }
+    let mut evicted = vec![];
+    for id in 10..20 {
+        replacer.touch(id).unwrap();
+        replacer.touch(id).unwrap();
+        evicted.push(replacer.evict().unwrap());
+    }
+    assert!(evicted.contains(&1));
+}
+
+#[test]
+fn pinned_frames() {
+    let replacer = GdsfReplacer::new(4);
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+    for id in [1, 2, 2, 3, 3, 3] {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: the least valuable frame is pinned, so the next one goes first,
+    // raising the inflation value.
+    replacer.pin(1).unwrap();
+    assert_eq!(replacer.remove(1), Err(EvictError::PinnedFrameRemoval(1)));
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: pinned frame kept its priority, which is now below the inflation
+    // value, so it goes first once unpinned, and does not deflate the value:
+    // the new frame still outranks the older one.
+    replacer.touch(4).unwrap();
+    replacer.unpin(1).unwrap();
+    assert_eq!(replacer.peek(), Some(1));
+    assert_eq!(replacer.evict(), Some(1));
+    replacer.touch(5).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), Some(5));
+}
@@ -0,0 +1,137 @@
+use {
+    evict::{EvictError, EvictionPolicy, HyperbolicConfig, HyperbolicReplacer},
+    std::sync::Arc,
+};
+
+#[test]
+fn basic_ops() {
+    let replacer = HyperbolicReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: all frames are sampled, the one with the lowest hits per time
+    // since insertion is evicted (frame 1: 2/3, frame 2: 1/2, frame 3: 1/1).
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn decay() {
+    let replacer = HyperbolicReplacer::new(2);
+
+    // Scenario: frame 1 has more hits, but they are spread over a long time,
+    // while frame 2 is accessed often since it has been inserted.
+    for _ in 0..10 {
+        replacer.touch(1).unwrap();
+    }
+    assert_eq!(replacer.touch(2), Ok(()));
+    assert_eq!(replacer.peek(), Some(1));
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+}
+
+#[test]
+fn cost() {
+    let replacer = HyperbolicReplacer::new(3);
+
+    // Scenario: expensive frames are kept longer.
+    replacer.touch_with_cost(1, 10.0).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    replacer.touch(1).unwrap(); // Cost is retained.
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(1));
+}
+
+#[test]
+fn sampled_eviction() {
+    let replacer = HyperbolicReplacer::with_config(HyperbolicConfig {
+        capacity: 1000,
+        samples: 8,
+        seed: 42,
+    });
+
+    // Scenario: half of the frames are hot, and victims are picked among just a
+    // few sampled frames, so a hot frame is evicted only if no cold frame is
+    // sampled.
+    for id in 0..1000 {
+        replacer.touch(id).unwrap();
+    }
+    for _ in 0..10 {
+        for id in 0..500 {
+            replacer.touch(id).unwrap();
+        }
+    }
+    let cold = (0..100)
+        .filter(|_| replacer.evict().unwrap() >= 500)
+        .count();
+    assert!(cold >= 95);
+}
+
+#[test]
+fn pinned_frames() {
+    let replacer = HyperbolicReplacer::new(3);
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+    for id in [1, 1, 1, 2, 3, 3] {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: frame 2 has the lowest priority, but it is pinned, so frame 1
+    // is the victim instead (frame 1: 3/5, frame 2: 1/2, frame 3: 2/1).
+    replacer.pin(2).unwrap();
+    assert_eq!(replacer.remove(2), Err(EvictError::PinnedFrameRemoval(2)));
+    assert_eq!(replacer.peek(), Some(1));
+
+    // Scenario: pinned frame is still accessed, so once unpinned, it outranks the
+    // rest (frame 1: 3/9, frame 2: 5/6, frame 3: 2/5).
+    for _ in 0..4 {
+        replacer.touch(2).unwrap();
+    }
+    replacer.unpin(2).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(2));
+}
+
+#[test]
+fn multi_threaded() {
+    use std::thread;
+
+    let n = 100;
+    let k = 20;
+    let replacer = Arc::new(HyperbolicReplacer::new(n * k));
+
+    // Concurrently register frames, and then access them concurrently again (which
+    // only requires a read lock).
+    let mut handles = vec![];
+    for i in 0..n {
+        let replacer = Arc::clone(&replacer);
+        handles.push(thread::spawn(move || {
+            for j in 0..k {
+                replacer.unpin(i * k + j).unwrap();
+                replacer.touch(i * k + j).unwrap();
+            }
+        }));
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(replacer.size(), n * k);
+
+    for _ in 0..n * k {
+        assert!(replacer.evict().is_some());
+    }
+    assert_eq!(replacer.size(), 0);
+}
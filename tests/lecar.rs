@@ -0,0 +1,102 @@
+use evict::{EvictError, EvictionPolicy, LecarConfig, LecarReplacer, LruReplacer};
+
+/// Number of misses on the hot frames `0..4`, which are accessed once per
+/// round, between scans of 8 new frames.
+fn hot_misses(replacer: &impl EvictionPolicy<usize>, rounds: usize) -> usize {
+    let touch = |id| {
+        if replacer.touch(id).is_ok() {
+            return false;
+        }
+        replacer.evict().unwrap();
+        replacer.touch(id).unwrap();
+        true
+    };
+    // Warm up the hot frames.
+    for _ in 0..4 {
+        (0..4).for_each(|id| _ = touch(id));
+    }
+
+    let mut misses = 0;
+    for round in 0..rounds {
+        misses += (0..4).filter(|&id| touch(id)).count();
+        (0..8).for_each(|i| _ = touch(100 + round * 8 + i));
+    }
+    misses
+}
+
+#[test]
+fn basic_ops() {
+    let replacer = LecarReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: both experts agree on the victim.
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: experts disagree, peek returns the frame the next eviction picks.
+    replacer.touch(3).unwrap();
+    replacer.touch(4).unwrap();
+    let victim = replacer.peek();
+    assert!(matches!(victim, Some(1 | 4)));
+    assert_eq!(replacer.evict(), victim);
+    assert_eq!(replacer.size(), 2);
+}
+
+#[test]
+fn learning() {
+    // Scenario: hot frames are accessed less recently than the scanned ones, so
+    // LRU keeps evicting them, while LeCaR learns to follow LFU.
+    let lru = hot_misses(&LruReplacer::new(8), 200);
+    assert!(lru > 3 * 200);
+    let lecar = hot_misses(&LecarReplacer::new(8), 200);
+    assert!(lecar < lru / 4);
+    let cacheus = hot_misses(&LecarReplacer::with_adaptive_learning_rate(8), 200);
+    assert!(cacheus < lru / 4);
+}
+
+#[test]
+fn seeded() {
+    let evictions = |seed| {
+        let replacer = LecarReplacer::with_config(LecarConfig {
+            capacity: 8,
+            seed,
+            ..LecarConfig::default()
+        });
+        hot_misses(&replacer, 50);
+        std::iter::from_fn(|| replacer.evict()).collect::<Vec<_>>()
+    };
+
+    // Scenario: the same seed results in the same eviction order.
+    assert_eq!(evictions(1), evictions(1));
+}
+
+#[test]
+fn pinned_frames() {
+    let replacer = LecarReplacer::new(3);
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+    for id in [1, 2, 3, 2, 3] {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: frame 1 is the victim of both experts, but it is pinned, so both
+    // of them move on to frame 2.
+    replacer.pin(1).unwrap();
+    assert_eq!(replacer.remove(1), Err(EvictError::PinnedFrameRemoval(1)));
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: pinned frame kept its position and access count, so it is the
+    // victim of both experts again once unpinned.
+    replacer.unpin(1).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), None);
+}
@@ -0,0 +1,106 @@
+use evict::{EvictError, EvictionPolicy, LfuConfig, LfuReplacer};
+
+#[test]
+fn basic_ops() {
+    let replacer = LfuReplacer::new(4);
+    assert_eq!(replacer.capacity(), 4);
+    assert_eq!(replacer.evict(), None);
+
+    // Scenario: frames with different access counts.
+    for id in [1, 2, 3, 4, 1, 1, 2, 3, 3, 3] {
+        replacer.touch(id).unwrap();
+    }
+    assert_eq!(replacer.size(), 4);
+    assert_eq!(replacer.touch(5), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: least frequently used frames are evicted first.
+    assert_eq!(replacer.peek(), Some(4));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn ties_broken_by_recency() {
+    let replacer = LfuReplacer::new(4);
+
+    for id in [1, 2, 3, 4] {
+        replacer.touch(id).unwrap();
+    }
+    // Scenario: frames 1 and 3 have the same access count, but frame 3 has been
+    // accessed more recently.
+    replacer.touch(3).unwrap();
+    replacer.touch(1).unwrap();
+    replacer.unpin(2).unwrap(); // Unpinning does not count as an access.
+
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(1));
+}
+
+#[test]
+fn dynamic_aging() {
+    let config = || LfuConfig {
+        capacity: 2,
+        dynamic_aging: true,
+    };
+
+    // Scenario: frame 1 was hot long ago, and is not accessed anymore. Without
+    // aging, it would stay in the replacer forever.
+    let replacer = LfuReplacer::with_config(LfuConfig {
+        dynamic_aging: false,
+        ..config()
+    });
+    for _ in 0..5 {
+        replacer.touch(1).unwrap();
+    }
+    for id in 2..10 {
+        replacer.touch(id).unwrap();
+        assert_eq!(replacer.evict(), Some(id));
+    }
+
+    // Scenario: with aging, every eviction raises the cache age, so the priority of
+    // new frames eventually catches up with the priority of frame 1.
+    let replacer = LfuReplacer::with_config(config());
+    for _ in 0..5 {
+        replacer.touch(1).unwrap();
+    }
+    for id in 2..6 {
+        replacer.touch(id).unwrap();
+        assert_eq!(replacer.evict(), Some(id));
+    }
+    replacer.touch(6).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(6));
+}
+
+#[test]
+fn pinned_frames_keep_counts() {
+    let replacer = LfuReplacer::new(4);
+
+    for id in [1, 1, 2, 3] {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: frame 1 stays in its bucket while pinned, and accesses to it are
+    // still counted.
+    replacer.pin(1).unwrap();
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), None);
+
+    // Scenario: once unpinned, frame 1 outlives frames accessed less often than
+    // it, even though they were accessed more recently.
+    for id in [4, 4, 5] {
+        replacer.touch(id).unwrap();
+    }
+    replacer.unpin(1).unwrap();
+    assert_eq!(replacer.evict(), Some(5));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), Some(1));
+}
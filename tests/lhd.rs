@@ -0,0 +1,107 @@
+use {
+    evict::{EvictError, EvictionPolicy, LhdConfig, LhdReplacer, SampledConfig, SampledReplacer},
+    std::collections::HashSet,
+};
+
+/// Number of hits when accessing frames in the given order.
+fn hits(replacer: &impl EvictionPolicy<usize>, accesses: impl IntoIterator<Item = usize>) -> usize {
+    let mut resident = HashSet::new();
+    let mut hits = 0;
+    for id in accesses {
+        if resident.contains(&id) {
+            hits += 1;
+        } else if resident.len() == replacer.capacity() {
+            resident.remove(&replacer.evict().unwrap());
+        }
+        resident.insert(id);
+        replacer.touch(id).unwrap();
+    }
+    hits
+}
+
+#[test]
+fn basic_ops() {
+    let replacer = LhdReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: nothing is learned yet, so the oldest frame without hits is
+    // evicted first.
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn size() {
+    let replacer = LhdReplacer::new(3);
+
+    // Scenario: large frames occupy more space, so they are evicted sooner.
+    replacer.touch_with_size(1, 10).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    replacer.touch(1).unwrap(); // Size is retained.
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+}
+
+#[test]
+fn learning() {
+    let config = || LhdConfig {
+        capacity: 48,
+        samples: 8,
+        seed: 42,
+    };
+
+    // Scenario: 32 hot frames are accessed in a loop, interleaved with a scan of
+    // frames accessed only once. LRU keeps the most recent 48 frames, which is
+    // not enough to hold the loop, while LHD learns that frames which have been
+    // hit are going to be hit again, and that the scanned frames are not.
+    let accesses = || (0..20_000).map(|i| if i % 2 == 0 { i / 2 % 32 } else { 1000 + i });
+    let lhd = hits(&LhdReplacer::with_config(config()), accesses());
+    let lru = hits(
+        &SampledReplacer::with_config(SampledConfig {
+            capacity: 48,
+            samples: 8,
+            seed: 42,
+            ..SampledConfig::default()
+        }),
+        accesses(),
+    );
+    assert!(lhd > 8_000);
+    assert!(lru < 1_000);
+}
+
+#[test]
+fn pinned_frames() {
+    let replacer = LhdReplacer::new(3);
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+    replacer.touch_with_size(1, 10).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+
+    // Scenario: large frame 1 has the lowest hit density, but it is pinned, so
+    // it is not sampled, and frame 2 goes instead.
+    replacer.pin(1).unwrap();
+    assert_eq!(replacer.size(), 2);
+    assert_eq!(replacer.remove(1), Err(EvictError::PinnedFrameRemoval(1)));
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: pinned frame kept its size, so it goes first once unpinned.
+    replacer.unpin(1).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), None);
+}
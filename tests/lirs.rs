@@ -0,0 +1,155 @@
+use {
+    evict::{AccessType, EvictError, EvictionPolicy, LirsConfig, LirsReplacer, LruReplacer},
+    std::collections::HashSet,
+};
+
+/// Access made as a part of a sequential scan.
+struct Scan;
+
+impl AccessType for Scan {
+    fn is_scan(&self) -> bool {
+        true
+    }
+}
+
+/// Replacer with two LIR frames and a single resident HIR frame.
+fn replacer(max_non_resident: usize) -> LirsReplacer<i32> {
+    LirsReplacer::with_config(LirsConfig {
+        capacity: 3,
+        hir_ratio: 0.3,
+        max_non_resident,
+    })
+}
+
+#[test]
+fn basic_ops() {
+    let replacer = replacer(3);
+    assert_eq!(replacer.capacity(), 3);
+
+    // Scenario: the first frames fill the LIR set, the rest are resident HIR.
+    // Stack: [1, 2, 3], queue: [3].
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: resident HIR frames are evicted first. Frame 3 stays in the stack
+    // as non-resident. Stack: [1, 2, 3*, 4], queue: [4].
+    assert_eq!(replacer.evict(), Some(3));
+    replacer.touch(4).unwrap();
+
+    // Scenario: frame 3 is re-referenced while still in the stack, so it becomes a
+    // LIR frame, and the least recent LIR frame (1) is demoted.
+    // Stack: [2, 4*, 3], queue: [1].
+    assert_eq!(replacer.evict(), Some(4));
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+
+    // Scenario: with no resident HIR frames left, LIR frames are evicted in their
+    // recency order.
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+/// Replays the trace against the replacer, evicting frames when it is full.
+/// Returns the number of hits.
+fn replay(replacer: &impl EvictionPolicy<i32>, trace: impl Iterator<Item = i32>) -> usize {
+    let mut resident = HashSet::new();
+    let mut hits = 0;
+    for id in trace {
+        if resident.contains(&id) {
+            hits += 1;
+        } else if resident.len() == replacer.capacity() {
+            resident.remove(&replacer.evict().unwrap());
+        }
+        resident.insert(id);
+        replacer.touch(id).unwrap();
+    }
+    hits
+}
+
+#[test]
+fn loop_resistance() {
+    // Scenario: loop over four frames with capacity of three. LRU evicts every
+    // frame right before it is accessed again, while LIRS keeps the LIR frames
+    // resident.
+    let trace = || (0..10).flat_map(|_| 1..=4);
+    assert_eq!(replay(&LruReplacer::new(3), trace()), 0);
+    assert_eq!(replay(&replacer(3), trace()), 18);
+}
+
+#[test]
+fn non_resident_limit() {
+    let replacer = replacer(1);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+
+    // Frames 3 and 4 are evicted in turn, only frame 4 is remembered as
+    // non-resident.
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+    replacer.touch(4).unwrap();
+    assert_eq!(replacer.evict(), Some(4));
+
+    // Frame 3 comes back as a resident HIR frame, so it is the next victim.
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.peek(), Some(3));
+    replacer.remove(3).unwrap();
+
+    // Frame 4 comes back as a LIR frame, demoting frame 1.
+    replacer.touch(4).unwrap();
+    assert_eq!(replacer.peek(), Some(1));
+}
+
+#[test]
+fn scan_access() {
+    let replacer = replacer(3);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+
+    // Scenario: frame accessed by a scan is not recorded in the stack, so no
+    // history is kept once it is evicted.
+    replacer.touch_with(3, Scan).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: scan over the frame in the stack does not promote it, while the
+    // regular access does.
+    replacer.touch_with(3, Scan).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+}
+
+#[test]
+fn pinned_hir_frame() {
+    let replacer = replacer(3);
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+
+    // Stack: [1, 2, 3], queue: [3].
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+
+    // Scenario: the only resident HIR frame is pinned, so the victim falls back
+    // to the least recent LIR frame. Pinned frame cannot be removed.
+    replacer.pin(3).unwrap();
+    assert_eq!(replacer.size(), 2);
+    assert_eq!(replacer.remove(3), Err(EvictError::PinnedFrameRemoval(3)));
+    assert_eq!(replacer.peek(), Some(1));
+    assert_eq!(replacer.evict(), Some(1));
+
+    // Scenario: once unpinned, the resident HIR frame is evicted before the
+    // remaining LIR frame.
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 2);
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), None);
+}
@@ -0,0 +1,73 @@
+use {
+    evict::{EvictError, EvictionPolicy, LrfuConfig, LrfuReplacer},
+    std::{thread::sleep, time::Duration},
+};
+
+#[test]
+fn basic_ops() {
+    let replacer = LrfuReplacer::new(3, 0.0);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    // Scenario: with `lambda` of 0, the replacer works as LFU.
+    for id in [1, 2, 3, 1, 1, 2] {
+        replacer.touch(id).unwrap();
+    }
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    assert_eq!(replacer.peek(), Some(3));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+
+    // Scenario: ties are broken by recency.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+}
+
+#[test]
+fn lambda_spectrum() {
+    let run = |lambda| {
+        let replacer = LrfuReplacer::with_config(LrfuConfig {
+            capacity: 2,
+            lambda,
+        });
+        for _ in 0..5 {
+            replacer.touch(1).unwrap();
+        }
+        sleep(Duration::from_millis(50));
+        replacer.touch(2).unwrap();
+        replacer.evict()
+    };
+
+    // Scenario: frame 1 has been accessed more often, so LFU-like replacer keeps
+    // it, while LRU-like replacer evicts it, as it has not been accessed recently.
+    assert_eq!(run(0.0), Some(2));
+    assert_eq!(run(0.001), Some(2));
+    assert_eq!(run(1.0), Some(1));
+}
+
+#[test]
+fn recency_and_frequency() {
+    let replacer = LrfuReplacer::new(3, 0.001);
+
+    for id in [1, 1, 2] {
+        replacer.touch(id).unwrap();
+    }
+    sleep(Duration::from_millis(30));
+    replacer.touch(3).unwrap();
+
+    // Scenario: frames 2 and 3 have been accessed once, but the reference to frame
+    // 2 has decayed more. Frame 1 has been accessed as long ago as frame 2, but its
+    // two references outweigh a single recent one.
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(1));
+}
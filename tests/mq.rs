@@ -0,0 +1,132 @@
+use evict::{EvictError, EvictionPolicy, MqConfig, MqReplacer};
+
+#[test]
+fn basic_ops() {
+    let replacer = MqReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: frame 1 is accessed twice, so it moves into the second queue,
+    // and the least recently used frame of the first queue is evicted.
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: frame 2 comes back, and its access count is restored from the
+    // history.
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn frequency_queues() {
+    let replacer = MqReplacer::with_config(MqConfig {
+        capacity: 4,
+        life_time: 100,
+        ..MqConfig::default()
+    });
+
+    // Scenario: frames 1 and 2 are accessed over and over, though not recently,
+    // while a stream of frames accessed only once passes through the replacer.
+    for _ in 0..4 {
+        replacer.touch(1).unwrap();
+        replacer.touch(2).unwrap();
+    }
+    for id in 10..20 {
+        if replacer.touch(id) == Err(EvictError::FrameReplacerFull) {
+            assert!(replacer.evict().unwrap() >= 10);
+            replacer.touch(id).unwrap();
+        }
+    }
+    assert_eq!(replacer.evict(), Some(18));
+    assert_eq!(replacer.evict(), Some(19));
+}
+
+#[test]
+fn life_time() {
+    let replacer = MqReplacer::with_config(MqConfig {
+        capacity: 3,
+        life_time: 2,
+        ..MqConfig::default()
+    });
+
+    // Scenario: frame 1 was hot long ago, but is not accessed anymore, so it is
+    // demoted queue by queue, until it becomes the victim.
+    for _ in 0..4 {
+        replacer.touch(1).unwrap();
+    }
+    for _ in 0..10 {
+        replacer.touch(2).unwrap();
+        replacer.touch(3).unwrap();
+    }
+    assert_eq!(replacer.evict(), Some(1));
+}
+
+#[test]
+fn history() {
+    let replacer = MqReplacer::with_config(MqConfig {
+        capacity: 2,
+        history_ratio: 1.0,
+        ..MqConfig::default()
+    });
+
+    // Scenario: evicted frame restores its access count when it comes back.
+    for _ in 0..4 {
+        replacer.touch(1).unwrap();
+    }
+    assert_eq!(replacer.evict(), Some(1));
+    replacer.touch(2).unwrap();
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(1));
+
+    // Scenario: removed frames are not remembered.
+    for _ in 0..4 {
+        replacer.touch(1).unwrap();
+    }
+    replacer.remove(1).unwrap();
+    replacer.touch(1).unwrap();
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+}
+
+#[test]
+fn pinned_frames() {
+    let replacer = MqReplacer::with_config(MqConfig {
+        capacity: 3,
+        life_time: 2,
+        ..MqConfig::default()
+    });
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+
+    // Scenario: frame 1 is the hottest one, and it is pinned, so it is skipped
+    // while frames 2 and 3 are accessed.
+    for _ in 0..4 {
+        replacer.touch(1).unwrap();
+    }
+    replacer.pin(1).unwrap();
+    assert_eq!(replacer.remove(1), Err(EvictError::PinnedFrameRemoval(1)));
+    for _ in 0..3 {
+        replacer.touch(2).unwrap();
+        replacer.touch(3).unwrap();
+    }
+    assert_eq!(replacer.peek(), Some(2));
+
+    // Scenario: pinned frame was not accessed, so it was demoted as usual, and
+    // goes first once unpinned.
+    replacer.unpin(1).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), None);
+}
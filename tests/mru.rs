@@ -0,0 +1,69 @@
+use evict::{EvictError, EvictionPolicy, MruReplacer};
+
+#[test]
+fn basic_ops() {
+    let replacer = MruReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: the most recently accessed frame is evicted first.
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.peek(), Some(1));
+    assert_eq!(replacer.evict(), Some(1));
+
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn cyclic_scan() {
+    let replacer = MruReplacer::new(4);
+
+    // Scenario: frames 0..5 are scanned over and over, but only 4 of them fit.
+    // Unlike LRU, which misses on every access, MRU keeps most of the frames.
+    let mut hits = 0;
+    for _ in 0..10 {
+        for id in 0..5 {
+            match replacer.touch(id) {
+                Err(EvictError::FrameReplacerFull) => {
+                    replacer.evict().unwrap();
+                    replacer.touch(id).unwrap();
+                }
+                _ => hits += 1,
+            }
+        }
+    }
+    assert!(hits > 25);
+}
+
+#[test]
+fn pinned_frames() {
+    let replacer = MruReplacer::new(3);
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+    for id in 1..=3 {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: the most recently accessed frame is pinned, so the victim is
+    // the next most recent one, and the pinned frame cannot be removed.
+    replacer.pin(3).unwrap();
+    assert_eq!(replacer.size(), 2);
+    assert_eq!(replacer.remove(3), Err(EvictError::PinnedFrameRemoval(3)));
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: once unpinned, the frame is the most recent one again.
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), None);
+}
@@ -0,0 +1,83 @@
+use evict::{EvictError, EvictionPolicy, RandomReplacer};
+
+#[test]
+fn basic_ops() {
+    let replacer = RandomReplacer::new(3, 42);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: peek returns the frame the next eviction picks.
+    let mut evicted = vec![];
+    while let Some(id) = replacer.peek() {
+        assert_eq!(replacer.evict(), Some(id));
+        evicted.push(id);
+    }
+    evicted.sort();
+    assert_eq!(evicted, vec![1, 2, 3]);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn seeded() {
+    let evictions = |seed| {
+        let replacer = RandomReplacer::new(100, seed);
+        for id in 0..100 {
+            replacer.touch(id).unwrap();
+        }
+        std::iter::from_fn(|| replacer.evict()).collect::<Vec<_>>()
+    };
+
+    // Scenario: the same seed results in the same eviction order.
+    assert_eq!(evictions(1), evictions(1));
+    assert_ne!(evictions(1), evictions(2));
+    assert_ne!(evictions(1), (0..100).collect::<Vec<_>>());
+}
+
+#[test]
+fn accesses_are_ignored() {
+    let evictions = |accesses: &[i32]| {
+        let replacer = RandomReplacer::new(10, 42);
+        for id in 0..10 {
+            replacer.touch(id).unwrap();
+        }
+        for &id in accesses {
+            replacer.touch(id).unwrap();
+        }
+        std::iter::from_fn(|| replacer.evict()).collect::<Vec<_>>()
+    };
+
+    // Scenario: victims are picked regardless of the access history.
+    assert_eq!(evictions(&[]), evictions(&[1, 1, 2, 3, 5, 8]));
+}
+
+#[test]
+fn pinned_frames() {
+    // Scenario: pinned frames are never picked, whatever the seed, and cannot be
+    // removed. Once unpinned, they are evictable again.
+    for seed in 0..20 {
+        let replacer = RandomReplacer::new(10, seed);
+        assert_eq!(replacer.pin(0), Err(EvictError::InvalidFrameId(0)));
+        for id in 0..10 {
+            replacer.touch(id).unwrap();
+        }
+        for id in [2, 5, 7] {
+            replacer.pin(id).unwrap();
+        }
+        assert_eq!(replacer.size(), 7);
+        assert_eq!(replacer.remove(5), Err(EvictError::PinnedFrameRemoval(5)));
+
+        let mut evicted = std::iter::from_fn(|| replacer.evict()).collect::<Vec<_>>();
+        evicted.sort();
+        assert_eq!(evicted, vec![0, 1, 3, 4, 6, 8, 9]);
+
+        replacer.unpin(5).unwrap();
+        assert_eq!(replacer.evict(), Some(5));
+        assert_eq!(replacer.evict(), None);
+    }
+}
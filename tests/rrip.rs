@@ -0,0 +1,172 @@
+use {
+    evict::{
+        AccessType,
+        DenseRripReplacer,
+        EvictError,
+        EvictionPolicy,
+        RripConfig,
+        RripInsertion,
+        RripReplacer,
+    },
+    std::sync::Arc,
+};
+
+/// Access made as a part of a sequential scan.
+struct Scan;
+
+impl AccessType for Scan {
+    fn is_scan(&self) -> bool {
+        true
+    }
+}
+
+fn replacer(capacity: usize, insertion: RripInsertion) -> RripReplacer<usize> {
+    RripReplacer::with_config(RripConfig {
+        capacity,
+        bits: 2,
+        insertion,
+    })
+}
+
+/// Number of misses when accessing frames `0..n` cyclically, once the
+/// replacer is full.
+fn cyclic_misses(replacer: &RripReplacer<usize>, n: usize, rounds: usize) -> usize {
+    let mut misses = 0;
+    for _ in 0..rounds {
+        for id in 0..n {
+            if replacer.touch(id) == Err(EvictError::FrameReplacerFull) {
+                misses += 1;
+                replacer.evict().unwrap();
+                replacer.touch(id).unwrap();
+            }
+        }
+    }
+    misses
+}
+
+#[test]
+fn scan_resistance() {
+    let replacer = replacer(4, RripInsertion::Static);
+
+    // Scenario: frames 1 and 2 are re-referenced, while a scan passes through
+    // the replacer. Scanned frames are inserted with distant re-reference
+    // interval, so they are evicted first.
+    for id in [1, 2, 1, 2] {
+        replacer.touch(id).unwrap();
+    }
+    for id in 10..100 {
+        if replacer.touch_with(id, Scan) == Err(EvictError::FrameReplacerFull) {
+            assert!(replacer.evict().unwrap() >= 10);
+            replacer.touch_with(id, Scan).unwrap();
+        }
+    }
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    assert!(replacer.evict().unwrap() >= 10);
+    assert!(replacer.evict().unwrap() >= 10);
+}
+
+#[test]
+fn insertion_policies() {
+    // Scenario: frames are accessed cyclically, but do not fit into the
+    // replacer. SRRIP thrashes, while BRRIP keeps some of the frames. DRRIP
+    // learns to follow BRRIP.
+    let run = |insertion| cyclic_misses(&replacer(1024, insertion), 1536, 20);
+    let (srrip, brrip, drrip) = (
+        run(RripInsertion::Static),
+        run(RripInsertion::Bimodal),
+        run(RripInsertion::Dynamic),
+    );
+    assert!(brrip < srrip);
+    assert!(drrip < srrip);
+}
+
+#[test]
+fn aging() {
+    let replacer = replacer(4, RripInsertion::Static);
+    for id in [1, 2, 3, 4, 1] {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: frame 1 is re-referenced, the first frame (from the hand) with
+    // the highest RRPV is picked, and the rest of the frames are aged.
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: new frame 5 takes the slot of frame 2. Frames 3 and 4 have
+    // distant RRPVs after aging, so they are evicted before it, in the order of
+    // the hand.
+    replacer.touch(5).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(4));
+
+    // Scenario: pinned frame keeps aging, and is evicted right away once
+    // unpinned.
+    replacer.pin(5).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    replacer.touch(6).unwrap();
+    replacer.unpin(5).unwrap();
+    assert_eq!(replacer.evict(), Some(5));
+    assert_eq!(replacer.evict(), Some(6));
+    assert_eq!(replacer.evict(), None);
+}
+
+#[test]
+fn dense() {
+    let replacer = DenseRripReplacer::with_config(RripConfig {
+        capacity: 3,
+        bits: 2,
+        insertion: RripInsertion::Static,
+    });
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.touch(3), Err(EvictError::InvalidFrameId(3)));
+
+    replacer.touch(0).unwrap();
+    replacer.touch(1).unwrap();
+    replacer.unpin(2).unwrap();
+    replacer.touch(0).unwrap();
+    assert_eq!(replacer.size(), 3);
+
+    // Scenario: pinned frame is kept, but is never picked as a victim.
+    replacer.pin(1).unwrap();
+    assert_eq!(replacer.remove(1), Err(EvictError::PinnedFrameRemoval(1)));
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(0));
+    assert_eq!(replacer.evict(), None);
+
+    replacer.unpin(1).unwrap();
+    replacer.remove(1).unwrap();
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn multi_threaded() {
+    use std::thread;
+
+    let n = 100;
+    let k = 20;
+    let replacer = Arc::new(RripReplacer::new(n * k));
+
+    // Concurrently register frames, and then access them concurrently again (which
+    // only requires a read lock).
+    let mut handles = vec![];
+    for i in 0..n {
+        let replacer = Arc::clone(&replacer);
+        handles.push(thread::spawn(move || {
+            for j in 0..k {
+                replacer.unpin(i * k + j).unwrap();
+                replacer.touch(i * k + j).unwrap();
+            }
+        }));
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(replacer.size(), n * k);
+
+    for _ in 0..n * k {
+        assert!(replacer.evict().is_some());
+    }
+    assert_eq!(replacer.size(), 0);
+}
@@ -0,0 +1,156 @@
+use {
+    evict::{EvictError, EvictionPolicy, S3FifoConfig, S3FifoReplacer},
+    std::sync::Arc,
+};
+
+#[test]
+fn basic_ops() {
+    let replacer = S3FifoReplacer::new(4);
+    assert_eq!(replacer.capacity(), 4);
+    assert_eq!(replacer.evict(), None);
+
+    // Scenario: new frames go into the small queue.
+    for id in [1, 2, 3, 4] {
+        replacer.touch(id).unwrap();
+    }
+    assert_eq!(replacer.size(), 4);
+    assert_eq!(replacer.touch(5), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: frame 2 is accessed while on probation, so it is moved into the
+    // main queue, other frames are evicted in FIFO order.
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.peek(), Some(1));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+
+    // Scenario: frame 1 is remembered in the ghost queue, so it goes straight into
+    // the main queue, and outlives the newer frame 5.
+    replacer.touch(1).unwrap();
+    replacer.touch(5).unwrap();
+    assert_eq!(replacer.evict(), Some(5));
+    assert_eq!(replacer.evict(), Some(1));
+}
+
+#[test]
+fn scan_resistance() {
+    let replacer = S3FifoReplacer::with_config(S3FifoConfig {
+        capacity: 10,
+        ..S3FifoConfig::default()
+    });
+
+    // Scenario: hot frames are accessed twice.
+    for id in (1..=5).chain(1..=5) {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: long scan of one-hit wonders only cycles through the small queue.
+    for id in 100..200 {
+        if replacer.touch(id) == Err(EvictError::FrameReplacerFull) {
+            assert!(replacer.evict().unwrap() >= 100);
+            replacer.touch(id).unwrap();
+        }
+    }
+
+    // Scenario: hot frames are still there, and are evicted last.
+    for _ in 0..5 {
+        assert!(replacer.evict().unwrap() >= 100);
+    }
+    for id in 1..=5 {
+        assert_eq!(replacer.evict(), Some(id));
+    }
+    assert_eq!(replacer.evict(), None);
+}
+
+#[test]
+fn main_queue_reinsertion() {
+    let replacer = S3FifoReplacer::new(4);
+
+    // Scenario: all frames are accessed while on probation, frame 1 a few more
+    // times than the others.
+    for id in [1, 2, 3, 4, 1, 2, 3, 4, 1, 1] {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: all frames are moved into the main queue, and reinserted at its
+    // tail, with their counters decremented. Frame 1 survives the most rounds.
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), Some(1));
+}
+
+#[test]
+fn peek_is_read_only() {
+    let replacer = S3FifoReplacer::new(4);
+    for id in [1, 2, 3, 4, 1, 2] {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: frames 1 and 2 would be moved into the main queue before frame 3
+    // is found. Peeking finds the same victim, without moving them.
+    assert_eq!(replacer.peek(), Some(3));
+    assert_eq!(replacer.peek(), Some(3));
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: the same trace results in the same victims, whether peeking
+    // before every access or not. Some of the frames are pinned for a while, so
+    // that they are skipped on eviction.
+    let peeking = S3FifoReplacer::new(8);
+    let replacer = S3FifoReplacer::new(8);
+    let mut pinned = None;
+    for id in (0..500).map(|i: i32| (i * i + i / 7) % 13) {
+        let victim = peeking.peek();
+        if replacer.touch(id).is_err() {
+            assert_eq!(replacer.evict(), victim);
+            assert_eq!(peeking.evict(), victim);
+            replacer.touch(id).unwrap();
+        }
+        peeking.touch(id).unwrap();
+        if id % 3 == 0 {
+            for r in [&peeking, &replacer] {
+                if let Some(pinned) = pinned {
+                    r.unpin(pinned).unwrap();
+                }
+                r.pin(id).unwrap();
+            }
+            pinned = Some(id);
+        }
+    }
+}
+
+#[test]
+fn multi_threaded() {
+    use std::thread;
+
+    let n = 100;
+    let k = 20;
+    let replacer = Arc::new(S3FifoReplacer::new(n * k));
+
+    // Concurrently register frames, and then access them concurrently again (which
+    // only requires a read lock).
+    let mut handles = vec![];
+    for i in 0..n {
+        let replacer = Arc::clone(&replacer);
+        handles.push(thread::spawn(move || {
+            for j in 0..k {
+                replacer.unpin(i * k + j).unwrap();
+                for _ in 0..5 {
+                    replacer.touch(i * k + j).unwrap();
+                }
+            }
+        }));
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(replacer.size(), n * k);
+
+    for _ in 0..n * k {
+        assert!(replacer.evict().is_some());
+    }
+    assert_eq!(replacer.size(), 0);
+}
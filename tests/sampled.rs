@@ -0,0 +1,142 @@
+use {
+    evict::{EvictError, EvictionPolicy, SampledConfig, SampledPolicy, SampledReplacer},
+    std::sync::Arc,
+};
+
+/// Number of cold frames among the first 100 victims, when half of the frames
+/// are hot.
+fn cold_victims(config: SampledConfig) -> usize {
+    let replacer = SampledReplacer::with_config(config);
+    for id in 0..1000 {
+        replacer.touch(id).unwrap();
+    }
+    for id in 0..500 {
+        replacer.touch(id).unwrap();
+    }
+    (0..100)
+        .filter(|_| replacer.evict().unwrap() >= 500)
+        .count()
+}
+
+#[test]
+fn basic_ops() {
+    let replacer = SampledReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: all frames are sampled, the least recently used one is evicted.
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: frame in the pool is ranked by its latest access.
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn lfu() {
+    let replacer = SampledReplacer::with_config(SampledConfig {
+        capacity: 3,
+        policy: SampledPolicy::Lfu,
+        ..SampledConfig::default()
+    });
+
+    // Scenario: the least frequently used frame is evicted, regardless of
+    // recency.
+    replacer.touch(1).unwrap();
+    replacer.touch(1).unwrap();
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(1));
+}
+
+#[test]
+fn sampled_eviction() {
+    // Scenario: victims are picked among just a few sampled frames, but the
+    // pool keeps the cold frames found in the previous samples.
+    let pooled = cold_victims(SampledConfig {
+        capacity: 1000,
+        seed: 42,
+        ..SampledConfig::default()
+    });
+    let unpooled = cold_victims(SampledConfig {
+        capacity: 1000,
+        pool_size: 1,
+        seed: 42,
+        ..SampledConfig::default()
+    });
+    assert!(pooled >= 99);
+    assert!(unpooled < pooled);
+}
+
+#[test]
+fn pinned_frames() {
+    let replacer = SampledReplacer::new(3);
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+    for id in 1..=3 {
+        replacer.touch(id).unwrap();
+    }
+    // The rest of the sample stays in the pool.
+    assert_eq!(replacer.evict(), Some(1));
+    replacer.touch(4).unwrap();
+
+    // Scenario: frame 2 is the least recently used one, and it is in the pool,
+    // but it is pinned, so it is dropped from the pool and frame 3 goes instead.
+    replacer.pin(2).unwrap();
+    assert_eq!(replacer.size(), 2);
+    assert_eq!(replacer.remove(2), Err(EvictError::PinnedFrameRemoval(2)));
+    assert_eq!(replacer.peek(), Some(3));
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: pinned frame kept the time of its last access, so it is sampled
+    // and evicted first once unpinned.
+    replacer.unpin(2).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), None);
+}
+
+#[test]
+fn multi_threaded() {
+    use std::thread;
+
+    let n = 100;
+    let k = 20;
+    let replacer = Arc::new(SampledReplacer::new(n * k));
+
+    // Concurrently register frames, and then access them concurrently again (which
+    // only requires a read lock).
+    let mut handles = vec![];
+    for i in 0..n {
+        let replacer = Arc::clone(&replacer);
+        handles.push(thread::spawn(move || {
+            for j in 0..k {
+                replacer.unpin(i * k + j).unwrap();
+                replacer.touch(i * k + j).unwrap();
+            }
+        }));
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(replacer.size(), n * k);
+
+    for _ in 0..n * k {
+        assert!(replacer.evict().is_some());
+    }
+    assert_eq!(replacer.size(), 0);
+}
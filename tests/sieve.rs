@@ -0,0 +1,107 @@
+use {
+    evict::{EvictError, EvictionPolicy, SieveReplacer},
+    std::sync::Arc,
+};
+
+#[test]
+fn basic_ops() {
+    let replacer = SieveReplacer::new(3);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: frame 1 is visited, so the hand clears its bit and moves on.
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: frame 1 retains its position, while new frame 4 is inserted at the
+    // head of the queue. The hand continues from where it stopped.
+    replacer.touch(4).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(4));
+
+    // Scenario: the hand wraps around.
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn one_hit_wonders() {
+    let replacer = SieveReplacer::new(4);
+
+    // Scenario: frames 1 and 2 are accessed over and over, while a stream of
+    // frames accessed only once passes through the replacer.
+    for id in 10..100 {
+        replacer.touch(1).unwrap();
+        replacer.touch(2).unwrap();
+        if replacer.touch(id) == Err(EvictError::FrameReplacerFull) {
+            assert!(replacer.evict().unwrap() >= 10);
+            replacer.touch(id).unwrap();
+        }
+    }
+
+    assert_eq!(replacer.evict(), Some(98));
+    assert_eq!(replacer.evict(), Some(99));
+}
+
+#[test]
+fn hand_keeps_position() {
+    let replacer = SieveReplacer::new(4);
+
+    for id in 1..=4 {
+        replacer.touch(id).unwrap();
+    }
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+
+    // Scenario: survivors are not moved to the head of the queue, the hand clears
+    // visited bits of frames 2 and 3, and stops at frame 4.
+    replacer.touch(5).unwrap();
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.evict(), Some(4));
+
+    // Scenario: the hand goes on towards the head, so the new frame 5 is evicted
+    // before the older frames 2 and 3, which are only reached once the hand wraps
+    // around.
+    assert_eq!(replacer.evict(), Some(5));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+}
+
+#[test]
+fn multi_threaded() {
+    use std::thread;
+
+    let n = 100;
+    let k = 20;
+    let replacer = Arc::new(SieveReplacer::new(n * k));
+
+    // Concurrently register frames, and then access them concurrently again (which
+    // only requires a read lock).
+    let mut handles = vec![];
+    for i in 0..n {
+        let replacer = Arc::clone(&replacer);
+        handles.push(thread::spawn(move || {
+            for j in 0..k {
+                replacer.unpin(i * k + j).unwrap();
+                replacer.touch(i * k + j).unwrap();
+            }
+        }));
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(replacer.size(), n * k);
+
+    for _ in 0..n * k {
+        assert!(replacer.evict().is_some());
+    }
+    assert_eq!(replacer.size(), 0);
+}
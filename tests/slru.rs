@@ -0,0 +1,104 @@
+use evict::{EvictError, EvictionPolicy, SlruConfig, SlruReplacer};
+
+#[test]
+fn basic_ops() {
+    let replacer = SlruReplacer::with_config(SlruConfig {
+        capacity: 4,
+        segments: vec![0.5, 0.5],
+    });
+    assert_eq!(replacer.capacity(), 4);
+
+    // Scenario: new frames are put into the probationary segment.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    replacer.unpin(4).unwrap();
+    assert_eq!(replacer.size(), 4);
+    assert_eq!(replacer.touch(5), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: accessed frames are promoted into the protected segment. Once it
+    // overflows, its LRU frame (1) is demoted back into the probationary one.
+    // Probationary: [4, 1], protected: [2, 3].
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    assert_eq!(replacer.peek(), Some(4));
+
+    // Scenario: victims are taken from the probationary segment first.
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn demotion_across_segments() {
+    // Protected segments hold a single frame each.
+    let replacer = SlruReplacer::with_config(SlruConfig {
+        capacity: 4,
+        segments: vec![0.25; 4],
+    });
+
+    for id in 1..=4 {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: frame 1 climbs up to the top segment.
+    for _ in 0..3 {
+        replacer.touch(1).unwrap();
+    }
+
+    // Scenario: frame 2 follows it, and demotes it into the segment below the top.
+    for _ in 0..3 {
+        replacer.touch(2).unwrap();
+    }
+
+    // Scenario: frame 3 climbs up to the segment below the top, and demotes frame
+    // 1 once more.
+    for _ in 0..2 {
+        replacer.touch(3).unwrap();
+    }
+
+    // Probationary: [4], protected: [1], [3], [2].
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(2));
+}
+
+#[test]
+fn scan_resistance() {
+    let replacer = SlruReplacer::new(10);
+
+    // Frames 1 and 2 are hot.
+    for id in [1, 2, 1, 2] {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: a long scan does not flush protected frames out.
+    for id in 10..100 {
+        replacer.touch(id).unwrap();
+        assert_eq!(replacer.evict(), Some(id));
+    }
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+}
+
+#[test]
+fn single_segment() {
+    let replacer = SlruReplacer::with_config(SlruConfig {
+        capacity: 3,
+        segments: vec![1.0],
+    });
+
+    // Scenario: with no protected segments, repeated accesses only refresh
+    // recency, i.e. the replacer works as a plain LRU.
+    for id in [1, 2, 3, 1, 1, 2] {
+        replacer.touch(id).unwrap();
+    }
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+}
@@ -0,0 +1,120 @@
+use evict::{EvictError, EvictionPolicy, TwoQConfig, TwoQReplacer};
+
+/// Replacer with `Kin` of one frame, and `Kout` of two frames.
+fn replacer() -> TwoQReplacer<i32> {
+    TwoQReplacer::with_config(TwoQConfig {
+        capacity: 4,
+        kin_ratio: 0.25,
+        kout_ratio: 0.5,
+    })
+}
+
+#[test]
+fn basic_ops() {
+    let replacer = replacer();
+    assert_eq!(replacer.capacity(), 4);
+
+    // Scenario: frames referenced for the first time are put into `A1in`.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    replacer.unpin(4).unwrap();
+    assert_eq!(replacer.size(), 4);
+    assert_eq!(replacer.touch(5), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: re-references of frames in `A1in` are correlated, so they do not
+    // affect the eviction order.
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: frames remembered in `A1out` are put into `Am` once referenced
+    // again. `A1in` is above its threshold, so it is still used for eviction.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: `A1in` is at its threshold, so victims are taken from `Am` in LRU
+    // order.
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn ghost_queue_limit() {
+    let replacer = replacer();
+
+    for id in 1..=4 {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: `A1out` holds only two identifiers, so frame 1 is forgotten.
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Frame 1 is put into `A1in` again, while frame 2 is considered hot.
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    assert_eq!(replacer.evict(), Some(4));
+    replacer.touch(5).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(5));
+}
+
+#[test]
+fn scan_resistance() {
+    let replacer = replacer();
+
+    // Frames 1 and 2 are hot.
+    for id in [1, 2] {
+        replacer.touch(id).unwrap();
+    }
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+    for id in [1, 2, 10, 11] {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: a long scan over a full replacer does not flush hot frames out.
+    for id in 12..100 {
+        assert_eq!(replacer.evict(), Some(id - 2));
+        replacer.touch(id).unwrap();
+    }
+    replacer.pin(98).unwrap();
+    replacer.pin(99).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(2));
+}
+
+#[test]
+fn pinned_frames() {
+    let replacer = replacer();
+    assert_eq!(replacer.pin(1), Err(EvictError::InvalidFrameId(1)));
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+
+    // Scenario: the head of `A1in` is pinned, so the next frame in `A1in` is
+    // evicted instead. Pinned frame cannot be removed.
+    replacer.pin(1).unwrap();
+    assert_eq!(replacer.size(), 2);
+    assert_eq!(replacer.remove(1), Err(EvictError::PinnedFrameRemoval(1)));
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: once unpinned, frame 1 is evicted from the head of `A1in`, and is
+    // remembered in `A1out`, so the next reference puts it into `Am`. `A1in` is at
+    // its threshold then, so frame 1 is the next victim.
+    replacer.unpin(1).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), None);
+}
@@ -0,0 +1,161 @@
+use evict::{EvictError, EvictionPolicy, WTinyLfuConfig, WTinyLfuReplacer};
+
+#[test]
+fn basic_ops() {
+    let replacer = WTinyLfuReplacer::new(4);
+    assert_eq!(replacer.capacity(), 4);
+    assert_eq!(replacer.evict(), None);
+
+    // Scenario: the window holds a single frame, older frames are moved into the
+    // main region while it has room.
+    for id in [1, 2, 3, 4] {
+        replacer.touch(id).unwrap();
+    }
+    assert_eq!(replacer.size(), 4);
+    assert_eq!(replacer.touch(5), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: window candidate is not more frequent than the main region's
+    // victim, so it is not admitted.
+    assert_eq!(replacer.peek(), Some(4));
+    assert_eq!(replacer.evict(), Some(4));
+
+    // Scenario: window is empty, frames are evicted from the probationary segment
+    // first.
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), None);
+    assert_eq!(replacer.size(), 0);
+}
+
+#[test]
+fn frequency_admission() {
+    let replacer = WTinyLfuReplacer::new(4);
+
+    // Scenario: frequently used frames settle in the main region.
+    for _ in 0..3 {
+        for id in [1, 2, 3] {
+            replacer.touch(id).unwrap();
+        }
+    }
+
+    // Scenario: a scan of frames accessed only once does not pollute the main
+    // region.
+    for id in 10..50 {
+        if replacer.touch(id) == Err(EvictError::FrameReplacerFull) {
+            assert!(replacer.evict().unwrap() >= 10);
+            replacer.touch(id).unwrap();
+        }
+    }
+    assert_eq!(replacer.evict(), Some(49));
+
+    // Scenario: a frame accessed more often than the main region's victim is
+    // admitted.
+    for _ in 0..5 {
+        replacer.touch(100).unwrap();
+    }
+    assert_eq!(replacer.evict(), Some(3));
+    replacer.touch(101).unwrap();
+    assert_eq!(replacer.evict(), Some(101));
+    assert_eq!(replacer.evict(), Some(100));
+}
+
+#[test]
+fn hill_climbing() {
+    let capacity = 64;
+    let replacer = WTinyLfuReplacer::with_config(WTinyLfuConfig {
+        capacity,
+        hill_climbing: true,
+        ..WTinyLfuConfig::default()
+    });
+
+    // Scenario: the window is resized while the workload is running, the replacer
+    // stays consistent.
+    let mut hits = 0;
+    for i in 0..20_000usize {
+        let id = if i % 3 == 0 { i % 50 } else { i % 500 };
+        match replacer.touch(id) {
+            Ok(()) => hits += 1,
+            Err(EvictError::FrameReplacerFull) => {
+                replacer.evict().unwrap();
+                replacer.touch(id).unwrap();
+            }
+            Err(e) => panic!("unexpected error: {e}"),
+        }
+        assert!(replacer.size() <= capacity);
+    }
+    assert!(hits > 0);
+
+    for _ in 0..capacity {
+        assert!(replacer.evict().is_some());
+    }
+    assert_eq!(replacer.evict(), None);
+}
+
+#[test]
+fn peek_is_read_only() {
+    // Scenario: the same trace results in the same victims, whether peeking
+    // before every access or not. The window is resized along the way, and some
+    // of the frames are pinned for a while.
+    let config = || WTinyLfuConfig {
+        capacity: 16,
+        hill_climbing: true,
+        ..WTinyLfuConfig::default()
+    };
+    let peeking = WTinyLfuReplacer::with_config(config());
+    let replacer = WTinyLfuReplacer::with_config(config());
+    let mut pinned = None;
+    for id in (0..5_000).map(|i: i32| (i * i + i / 7) % 29) {
+        let victim = peeking.peek();
+        assert_eq!(peeking.peek(), victim);
+        if replacer.touch(id).is_err() {
+            assert_eq!(replacer.evict(), victim);
+            assert_eq!(peeking.evict(), victim);
+            replacer.touch(id).unwrap();
+        }
+        peeking.touch(id).unwrap();
+        if id % 5 == 0 {
+            for r in [&peeking, &replacer] {
+                if let Some(pinned) = pinned {
+                    r.unpin(pinned).unwrap();
+                }
+                r.pin(id).unwrap();
+            }
+            pinned = Some(id);
+        }
+    }
+}
+
+#[test]
+fn multi_threaded() {
+    use std::{sync::Arc, thread};
+
+    let n = 100;
+    let k = 20;
+    let replacer = Arc::new(WTinyLfuReplacer::new(n * k));
+    for id in 0..n * k {
+        replacer.touch(id).unwrap();
+    }
+
+    // Scenario: accesses to resident frames only require a read lock, they are
+    // recorded concurrently and applied later.
+    let mut handles = vec![];
+    for i in 0..n {
+        let replacer = Arc::clone(&replacer);
+        handles.push(thread::spawn(move || {
+            for j in 0..k {
+                replacer.touch(i * k + j).unwrap();
+            }
+        }));
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(replacer.size(), n * k);
+
+    for _ in 0..n * k {
+        assert!(replacer.evict().is_some());
+    }
+    assert_eq!(replacer.evict(), None);
+}
@@ -0,0 +1,103 @@
+use {
+    evict::{EvictError, EvictionPolicy, WsClockReplacer},
+    std::{sync::Arc, thread::sleep, time::Duration},
+};
+
+#[test]
+fn basic_ops() {
+    let replacer = WsClockReplacer::new(3, 100);
+    assert_eq!(replacer.capacity(), 3);
+    assert_eq!(replacer.evict(), None);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.unpin(3).unwrap();
+    assert_eq!(replacer.size(), 3);
+    assert_eq!(replacer.touch(4), Err(EvictError::FrameReplacerFull));
+
+    // Scenario: all frames are in the working set, so the hand completes the
+    // revolution, and stops at the frame used the longest time ago.
+    assert_eq!(replacer.peek(), Some(1));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.size(), 2);
+}
+
+#[test]
+fn working_set() {
+    let replacer = WsClockReplacer::new(3, 100);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+
+    // Scenario: once the window elapses, frames leave the working set, unless
+    // they are referenced again.
+    sleep(Duration::from_millis(110));
+    replacer.touch(1).unwrap();
+    assert_eq!(replacer.peek(), Some(2));
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: the hand recorded the time of the last use of frame 1, so it
+    // stays in the working set, while frame 3 does not.
+    assert_eq!(replacer.evict(), Some(3));
+
+    // Scenario: new frame 4 is in the working set as well, frame 1 has been used
+    // before it.
+    replacer.touch(4).unwrap();
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), Some(4));
+    assert_eq!(replacer.evict(), None);
+}
+
+#[test]
+fn pinned_frames() {
+    let replacer = WsClockReplacer::new(3, 50);
+
+    replacer.touch(1).unwrap();
+    replacer.touch(2).unwrap();
+    replacer.touch(3).unwrap();
+    sleep(Duration::from_millis(60));
+
+    // Scenario: frame 1 is no longer in the working set, but it is pinned, so the
+    // hand skips it, and stops at frame 2.
+    replacer.pin(1).unwrap();
+    assert_eq!(replacer.evict(), Some(2));
+
+    // Scenario: once unpinned, frame 1 is evicted when the hand reaches it again,
+    // after frame 3.
+    replacer.unpin(1).unwrap();
+    assert_eq!(replacer.evict(), Some(3));
+    assert_eq!(replacer.evict(), Some(1));
+    assert_eq!(replacer.evict(), None);
+}
+
+#[test]
+fn multi_threaded() {
+    use std::thread;
+
+    let n = 100;
+    let k = 20;
+    let replacer = Arc::new(WsClockReplacer::new(n * k, 100));
+
+    // Concurrently register frames, and then access them concurrently again (which
+    // only requires a read lock).
+    let mut handles = vec![];
+    for i in 0..n {
+        let replacer = Arc::clone(&replacer);
+        handles.push(thread::spawn(move || {
+            for j in 0..k {
+                replacer.unpin(i * k + j).unwrap();
+                replacer.touch(i * k + j).unwrap();
+            }
+        }));
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(replacer.size(), n * k);
+
+    for _ in 0..n * k {
+        assert!(replacer.evict().is_some());
+    }
+    assert_eq!(replacer.size(), 0);
+}